@@ -0,0 +1,46 @@
+//! Traits for enums with generated helpers.
+//!
+//! `enum-utility-macros` implements these traits when the `traits` option is given, so generic
+//! code can work with any enum which has a TagEnum, a RefEnum or a MutEnum:
+//! ```rust,ignore
+//! #[generate_enum_helper(TagEnum, RefEnum, traits)]
+//! enum MyEnum { Int32(i32), Float(f32) }
+//!
+//! fn log<E: Tagged>(e: &E) where E::Tag: Debug {
+//!     println!("{:?}", e.tag());
+//! }
+//! ```
+#![no_std]
+
+use core::hash::Hash;
+
+/// An enum with a TagEnum. Implemented for the input enum, the RefEnum and the MutEnum.
+pub trait Tagged {
+    /// The TagEnum, which has one unit variant per variant of the enum.
+    type Tag: Copy + Eq + Hash;
+
+    /// The variant of `self` as a tag.
+    fn tag(&self) -> Self::Tag;
+}
+
+/// An enum with a RefEnum. Implemented for the input enum and the MutEnum.
+pub trait HasRefView {
+    /// The RefEnum, which holds references to the fields of a variant.
+    type Ref<'a>
+    where
+        Self: 'a;
+
+    /// Borrows the fields of the current variant.
+    fn view(&self) -> Self::Ref<'_>;
+}
+
+/// An enum with a MutEnum. Implemented for the input enum and the MutEnum.
+pub trait HasMutView {
+    /// The MutEnum, which holds mutable references to the fields of a variant.
+    type Mut<'a>
+    where
+        Self: 'a;
+
+    /// Mutably borrows the fields of the current variant.
+    fn view_mut(&mut self) -> Self::Mut<'_>;
+}
@@ -0,0 +1,126 @@
+use proc_macro2::{Ident, Span};
+use syn::LitStr;
+
+/// Case style of generated names, selected with `rename_all = "..."`.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum RenameRule {
+    /// `ishttpserver`
+    Lowercase,
+    /// `ISHTTPSERVER`
+    Uppercase,
+    /// `IsHttpServer`
+    PascalCase,
+    /// `isHttpServer`
+    CamelCase,
+    /// `is_http_server`
+    #[default]
+    SnakeCase,
+    /// `IS_HTTP_SERVER`
+    ScreamingSnakeCase,
+    /// `is-http-server`, only for strings
+    KebabCase,
+    /// `IS-HTTP-SERVER`, only for strings
+    ScreamingKebabCase,
+}
+
+/// Names accepted by `rename_all`.
+const RULES: &[(&str, RenameRule)] = &[
+    ("lowercase", RenameRule::Lowercase),
+    ("UPPERCASE", RenameRule::Uppercase),
+    ("PascalCase", RenameRule::PascalCase),
+    ("camelCase", RenameRule::CamelCase),
+    ("snake_case", RenameRule::SnakeCase),
+    ("SCREAMING_SNAKE_CASE", RenameRule::ScreamingSnakeCase),
+    ("kebab-case", RenameRule::KebabCase),
+    ("SCREAMING-KEBAB-CASE", RenameRule::ScreamingKebabCase),
+];
+
+impl RenameRule {
+    pub(crate) fn parse(lit: &LitStr) -> syn::Result<Self> {
+        let value = lit.value();
+        match RULES.iter().find(|(name, _)| *name == value) {
+            Some((_, rule)) => Ok(*rule),
+            None => Err(syn::Error::new(
+                lit.span(),
+                format!(
+                    "unknown case style `{value}`, expected one of: {}",
+                    RULES
+                        .iter()
+                        .map(|(name, _)| *name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )),
+        }
+    }
+
+    /// Joins `words` in this case style. The words must be lowercase.
+    pub(crate) fn apply(self, words: &[String]) -> String {
+        let capitalize = |word: &String| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        };
+        match self {
+            RenameRule::Lowercase => words.concat(),
+            RenameRule::Uppercase => words.concat().to_uppercase(),
+            RenameRule::PascalCase => words.iter().map(capitalize).collect(),
+            RenameRule::CamelCase => {
+                let mut words = words.iter();
+                let first = words.next().cloned().unwrap_or_default();
+                first + &words.map(capitalize).collect::<String>()
+            }
+            RenameRule::SnakeCase => words.join("_"),
+            RenameRule::ScreamingSnakeCase => words.join("_").to_uppercase(),
+            RenameRule::KebabCase => words.join("-"),
+            RenameRule::ScreamingKebabCase => words.join("-").to_uppercase(),
+        }
+    }
+
+    /// Whether names in this case style are valid identifiers.
+    pub(crate) fn is_identifier(self) -> bool {
+        !matches!(self, RenameRule::KebabCase | RenameRule::ScreamingKebabCase)
+    }
+
+    /// Whether names in this case style trigger the `non_snake_case` lint.
+    pub(crate) fn is_snake_case(self) -> bool {
+        matches!(self, RenameRule::Lowercase | RenameRule::SnakeCase)
+    }
+}
+
+/// Splits `name` into lowercase words. Words are separated by `_` and by case changes:
+/// `HTTPServer` is `http`, `server` and `V2Beta` is `v2`, `beta`. Digits belong to the
+/// preceding word.
+pub(crate) fn words(name: &str) -> Vec<String> {
+    let mut words = vec![];
+    for part in name.split(|c: char| !c.is_alphanumeric()) {
+        let chars: Vec<char> = part.chars().collect();
+        let mut word = String::new();
+        for (i, &c) in chars.iter().enumerate() {
+            let boundary = i > 0 && c.is_uppercase() && {
+                let previous = chars[i - 1];
+                let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+                !previous.is_uppercase() || next_is_lower
+            };
+            if boundary && !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            word.extend(c.to_lowercase());
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+    }
+    words
+}
+
+/// Creates an identifier for a generated name. Keywords are emitted as raw identifiers, except
+/// the ones which can not be raw. Those get a trailing `_`.
+pub(crate) fn ident(name: &str, span: Span) -> Ident {
+    match name {
+        "self" | "Self" | "super" | "crate" | "_" => Ident::new(&format!("{name}_"), span),
+        _ if syn::parse_str::<Ident>(name).is_ok() => Ident::new(name, span),
+        _ => Ident::new_raw(name, span),
+    }
+}
@@ -0,0 +1,911 @@
+use crate::{
+    case::{self, RenameRule},
+    diagnostics::Diagnostics,
+    InputEnum,
+};
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use quote::quote;
+use syn::{
+    ext::IdentExt, meta::ParseNestedMeta, parenthesized, parse::Parser, punctuated::Punctuated,
+    spanned::Spanned, token, Attribute, Lifetime, LitStr, Path, Token, Visibility,
+};
+
+/// Name of the attribute holding per-variant options.
+pub(crate) const HELPER_ATTRIBUTE: &str = "enum_helper";
+
+/// Helpers which are generated once per variant. Only these can be skipped for a variant.
+const VARIANT_HELPERS: &[&str] = &[
+    "is",
+    "unwrap",
+    "unwrap_ref",
+    "unwrap_mut",
+    "get",
+    "get_ref",
+    "get_mut",
+    "into",
+    "expect",
+    "expect_ref",
+    "expect_mut",
+    "unwrap_or",
+    "unwrap_or_default",
+    "unwrap_ref_or",
+    "unwrap_mut_or",
+    "map",
+    "update",
+    "new",
+    "take",
+    "replace",
+    "insert",
+    "get_or_insert",
+    "fields",
+    "fields_mut",
+    "try_unwrap",
+    "from",
+];
+
+/// All options which enable a generated enum or function.
+const OPTIONS: &[&str] = &[
+    "TagEnum",
+    "RefEnum",
+    "MutEnum",
+    "is",
+    "unwrap",
+    "unwrap_ref",
+    "unwrap_mut",
+    "to_tag",
+    "as_ref",
+    "as_mut",
+    "get",
+    "get_ref",
+    "get_mut",
+    "into",
+    "expect",
+    "expect_ref",
+    "expect_mut",
+    "unwrap_or",
+    "unwrap_or_default",
+    "unwrap_ref_or",
+    "unwrap_mut_or",
+    "map",
+    "update",
+    "new",
+    "take",
+    "replace",
+    "insert",
+    "get_or_insert",
+    "fields",
+    "fields_mut",
+    "shared_fields",
+    "shared_fields_mut",
+    "metadata",
+    "try_unwrap",
+    "from",
+    "traits",
+];
+
+/// Named groups of options. They can be combined with exclusions, e.g. `all, !unwrap`.
+const PRESETS: &[(&str, &[&str])] = &[
+    ("all", OPTIONS),
+    (
+        "owned",
+        &["is", "unwrap", "get", "into", "expect", "unwrap_or", "map"],
+    ),
+    (
+        "borrowed",
+        &[
+            "RefEnum",
+            "unwrap_ref",
+            "get_ref",
+            "as_ref",
+            "expect_ref",
+            "unwrap_ref_or",
+            "fields",
+            "shared_fields",
+        ],
+    ),
+    (
+        "mutable",
+        &[
+            "MutEnum",
+            "unwrap_mut",
+            "get_mut",
+            "as_mut",
+            "expect_mut",
+            "unwrap_mut_or",
+            "update",
+            "take",
+            "replace",
+            "insert",
+            "get_or_insert",
+            "fields_mut",
+            "shared_fields_mut",
+        ],
+    ),
+    (
+        "views",
+        &[
+            "TagEnum", "RefEnum", "MutEnum", "to_tag", "as_ref", "as_mut",
+        ],
+    ),
+];
+
+/// Options which are not enabled by presets, since they do not compile for every enum.
+/// `unwrap_or_default` requires all payloads to implement `Default`, `from` requires the
+/// payload types to be distinct and `traits` requires the `enum-utility-traits` crate.
+const OPT_IN: &[&str] = &["unwrap_or_default", "from", "traits"];
+
+/// Options which can only be generated together with one of the `required` options:
+/// `(option, required)`.
+const DEPENDENCIES: &[(&str, &[&str])] = &[
+    ("to_tag", &["TagEnum"]),
+    ("as_ref", &["RefEnum"]),
+    ("as_mut", &["MutEnum"]),
+    ("traits", &["TagEnum", "RefEnum", "MutEnum"]),
+];
+
+/// Options which borrow the payloads and can not be generated for enums without any fields.
+const NEEDS_FIELDS: &[&str] = &["RefEnum", "MutEnum", "as_ref", "as_mut"];
+
+/// Typed form of the arguments passed to `generate_enum_helper` or `#[enum_helper(...)]`.
+///
+/// Every option is `None` unless it was requested. Options which take parameters use the
+/// nested `Option(key = value, flag)` form, e.g. `TagEnum(derive(Ord, PartialOrd))`.
+#[derive(Default)]
+pub(crate) struct Config {
+    /// Visibility of all generated functions which do not specify their own.
+    pub(crate) helpers_vis: Option<Visibility>,
+    /// Case style of the names of generated functions. Defaults to `snake_case`.
+    pub(crate) rename_all: Option<RenameRule>,
+
+    pub(crate) tag_enum: Option<TagEnumConfig>,
+    pub(crate) ref_enum: Option<RefEnumConfig>,
+    pub(crate) mut_enum: Option<RefEnumConfig>,
+    pub(crate) traits: Option<TraitsConfig>,
+
+    pub(crate) is: Option<FunctionConfig>,
+    pub(crate) unwrap: Option<FunctionConfig>,
+    pub(crate) unwrap_ref: Option<FunctionConfig>,
+    pub(crate) unwrap_mut: Option<FunctionConfig>,
+    pub(crate) to_tag: Option<FunctionConfig>,
+    pub(crate) as_ref: Option<FunctionConfig>,
+    pub(crate) as_mut: Option<FunctionConfig>,
+    pub(crate) get: Option<FunctionConfig>,
+    pub(crate) get_ref: Option<FunctionConfig>,
+    pub(crate) get_mut: Option<FunctionConfig>,
+    pub(crate) into: Option<FunctionConfig>,
+    pub(crate) expect: Option<FunctionConfig>,
+    pub(crate) expect_ref: Option<FunctionConfig>,
+    pub(crate) expect_mut: Option<FunctionConfig>,
+    pub(crate) unwrap_or: Option<FunctionConfig>,
+    pub(crate) unwrap_or_default: Option<FunctionConfig>,
+    pub(crate) unwrap_ref_or: Option<FunctionConfig>,
+    pub(crate) unwrap_mut_or: Option<FunctionConfig>,
+    pub(crate) map: Option<FunctionConfig>,
+    pub(crate) update: Option<FunctionConfig>,
+    pub(crate) new: Option<FunctionConfig>,
+    pub(crate) take: Option<FunctionConfig>,
+    pub(crate) replace: Option<FunctionConfig>,
+    pub(crate) insert: Option<FunctionConfig>,
+    pub(crate) get_or_insert: Option<FunctionConfig>,
+    pub(crate) fields: Option<FunctionConfig>,
+    pub(crate) fields_mut: Option<FunctionConfig>,
+    pub(crate) shared_fields: Option<FunctionConfig>,
+    pub(crate) shared_fields_mut: Option<FunctionConfig>,
+    pub(crate) metadata: Option<FunctionConfig>,
+    pub(crate) try_unwrap: Option<FunctionConfig>,
+    pub(crate) from: Option<FunctionConfig>,
+
+    /// Options given by name rather than through a preset, with the span of the name.
+    requested: Vec<(&'static str, Span)>,
+}
+
+#[derive(Default)]
+pub(crate) struct TagEnumConfig {
+    /// Name of the TagEnum. Defaults to `{Enum}Tag`.
+    pub(crate) name: Option<Ident>,
+    /// Visibility of the TagEnum. Defaults to the visibility of the input enum.
+    pub(crate) vis: Option<Visibility>,
+    /// Derives added to the default `Clone, Copy, Debug, PartialEq, Eq, Hash`.
+    pub(crate) derives: Vec<Path>,
+    /// Generates `as_str`, `Display` and `FromStr` for the TagEnum.
+    pub(crate) strings: Option<TagStringsConfig>,
+}
+
+#[derive(Default)]
+pub(crate) struct TagStringsConfig {
+    /// Case style of the strings. Defaults to the variant names.
+    pub(crate) rename_all: Option<RenameRule>,
+    /// Parses strings ignoring ASCII case.
+    pub(crate) case_insensitive: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct TraitsConfig {
+    /// Path of the `enum-utility-traits` crate. Defaults to `::enum_utility_traits`.
+    pub(crate) path: Option<Path>,
+}
+
+#[derive(Default)]
+pub(crate) struct RefEnumConfig {
+    /// Name of the generated enum. Defaults to `{Enum}Ref` or `{Enum}Mut`.
+    pub(crate) name: Option<Ident>,
+    /// Visibility of the generated enum. Defaults to the visibility of the input enum.
+    pub(crate) vis: Option<Visibility>,
+    /// Derives added to the ones copied from the input enum.
+    pub(crate) derives: Vec<Path>,
+    /// Lifetime of the references stored in the generated enum.
+    pub(crate) lifetime: Option<Lifetime>,
+    /// Implements `From` for references to the input enum.
+    pub(crate) from: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct FunctionConfig {
+    /// Visibility of the generated functions. Overrides `helpers_vis`.
+    pub(crate) vis: Option<Visibility>,
+    /// Takes `impl Into<T>` arguments instead of `T`. Only valid for `new`.
+    pub(crate) into: bool,
+    /// Name of the generated function, used as given. Only valid for `as_ref` and `as_mut`.
+    pub(crate) name: Option<Ident>,
+    /// Leaves out variants whose payload type is ambiguous instead of reporting them. Only
+    /// valid for `from`.
+    pub(crate) skip_ambiguous: bool,
+}
+
+/// Options of a single variant, given by `#[enum_helper(...)]` on the variant.
+#[derive(Default)]
+pub(crate) struct VariantConfig {
+    /// Skips all per-variant helpers.
+    pub(crate) skip_all: bool,
+    /// Per-variant helpers which are not generated for this variant.
+    pub(crate) skip: Vec<String>,
+    /// Replaces the snake case variant name in the names of generated functions.
+    pub(crate) rename: Option<String>,
+}
+
+impl Config {
+    /// Parses the arguments of `generate_enum_helper`. All invalid options are reported at once.
+    pub(crate) fn parse(tokens: TokenStream) -> syn::Result<Self> {
+        Self::parse_all([tokens])
+    }
+
+    /// Parses all `#[enum_helper(...)]` container attributes of a `#[derive(EnumHelper)]` enum.
+    pub(crate) fn parse_attributes(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut lists = vec![];
+        let mut diagnostics = Diagnostics::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident(HELPER_ATTRIBUTE)) {
+            if let Some(list) = diagnostics.check(attr.meta.require_list()) {
+                lists.push(list.tokens.clone());
+            }
+        }
+        let config = diagnostics.check(Self::parse_all(lists));
+        diagnostics.finish().map(|()| config.unwrap_or_default())
+    }
+
+    /// Parses the options of all `streams`. Presets and exclusions are applied after all options
+    /// are parsed: explicitly configured options take precedence over presets and exclusions
+    /// take precedence over everything else.
+    fn parse_all(streams: impl IntoIterator<Item = TokenStream>) -> syn::Result<Self> {
+        let mut config = Self::default();
+        let mut enabled = vec![];
+        let mut excluded = vec![];
+        let mut requested = vec![];
+        let mut diagnostics = Diagnostics::default();
+        for tokens in streams {
+            for (bang, tokens) in split_options(tokens) {
+                if let (Some(bang), true) = (bang, tokens.is_empty()) {
+                    diagnostics.push(syn::Error::new(bang, "expected an option after `!`"));
+                }
+                let parser = syn::meta::parser(|meta| {
+                    let result = match bang {
+                        Some(_) => parse_exclusion(&meta).map(|names| excluded.extend(names)),
+                        None => config.parse_option(&meta, &mut enabled).map(|()| {
+                            let ident = meta.path.get_ident();
+                            let option = OPTIONS.iter().find(|o| ident.is_some_and(|i| i == *o));
+                            requested.extend(option.map(|option| (*option, meta.path.span())));
+                        }),
+                    };
+                    recover(&meta, result, &mut diagnostics)
+                });
+                let result = parser.parse2(tokens);
+                diagnostics.check(result);
+            }
+        }
+
+        for name in enabled {
+            if let Some(slot) = config.slot(name) {
+                slot.enable();
+            }
+        }
+        for name in excluded {
+            config.disable(name);
+        }
+
+        config.requested = requested;
+
+        for (option, required) in DEPENDENCIES {
+            if !config.is_enabled(option) || required.iter().any(|r| config.is_enabled(r)) {
+                continue;
+            }
+            // Options enabled by a preset are left out together with the options they require.
+            let Some(span) = config.requested_span(option) else {
+                config.disable(option);
+                continue;
+            };
+            let message = match required {
+                [required] => {
+                    format!("`{option}` requires `{required}`, add `{required}` to the options")
+                }
+                [others @ .., last] => format!(
+                    "`{option}` requires {} or `{last}`, add one of them to the options",
+                    others
+                        .iter()
+                        .map(|o| format!("`{o}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                [] => unreachable!("every dependency requires an option"),
+            };
+            diagnostics.push(syn::Error::new(span, message));
+        }
+        diagnostics.finish().map(|()| config)
+    }
+
+    /// Leaves out the options enabled by presets which can not be generated for `input`. Such
+    /// options given by name are reported.
+    pub(crate) fn restrict_to(&mut self, input: &InputEnum) -> syn::Result<()> {
+        let mut diagnostics = Diagnostics::default();
+        if !(0..input.variant_count()).any(|i| input.has_fields(i)) {
+            for option in NEEDS_FIELDS {
+                if !self.is_enabled(option) {
+                    continue;
+                }
+                match self.requested_span(option) {
+                    Some(span) => diagnostics.push(syn::Error::new(
+                        span,
+                        format!("`{option}` can not be generated for an enum without fields"),
+                    )),
+                    None => self.disable(option),
+                }
+            }
+        }
+        diagnostics.finish()
+    }
+
+    /// Name of the TagEnum. All helpers referring to the TagEnum use this name.
+    pub(crate) fn tag_enum_ident(&self, input: &InputEnum) -> Ident {
+        let name = self.tag_enum.as_ref().and_then(|c| c.name.clone());
+        name.unwrap_or_else(|| default_ident(input, "Tag"))
+    }
+
+    /// Name of the RefEnum. All helpers referring to the RefEnum use this name.
+    pub(crate) fn ref_enum_ident(&self, input: &InputEnum) -> Ident {
+        let name = self.ref_enum.as_ref().and_then(|c| c.name.clone());
+        name.unwrap_or_else(|| default_ident(input, "Ref"))
+    }
+
+    /// Name of the MutEnum. All helpers referring to the MutEnum use this name.
+    pub(crate) fn mut_enum_ident(&self, input: &InputEnum) -> Ident {
+        let name = self.mut_enum.as_ref().and_then(|c| c.name.clone());
+        name.unwrap_or_else(|| default_ident(input, "Mut"))
+    }
+
+    /// Name of the error returned by `try_unwrap` functions.
+    pub(crate) fn wrong_variant_ident(&self, input: &InputEnum) -> Ident {
+        default_ident(input, "WrongVariant")
+    }
+
+    /// Path of the `enum-utility-traits` crate, used by the trait impls of `traits`.
+    pub(crate) fn traits_path(&self) -> TokenStream {
+        match self.traits.as_ref().and_then(|c| c.path.as_ref()) {
+            Some(path) => quote! { #path },
+            None => quote! { ::enum_utility_traits },
+        }
+    }
+
+    /// Name of the error returned when parsing a TagEnum fails, e.g. `ParseMyEnumTagError`.
+    pub(crate) fn parse_tag_error_ident(&self, input: &InputEnum) -> Ident {
+        let tag_enum = self.tag_enum_ident(input);
+        Ident::new(
+            &format!("Parse{}Error", tag_enum.unraw()),
+            Span::call_site(),
+        )
+    }
+
+    /// Visibility of functions generated for the option `fc`.
+    pub(crate) fn function_vis<'c>(
+        &'c self,
+        fc: &'c FunctionConfig,
+        input: &'c InputEnum,
+    ) -> &'c Visibility {
+        fc.vis
+            .as_ref()
+            .or(self.helpers_vis.as_ref())
+            .unwrap_or_else(|| input.vis())
+    }
+
+    /// Name of the function generated by `template` for variant `i`. `{}` in the template is
+    /// replaced by the variant name, e.g. `unwrap_ref_{}`. The name has the span of the variant,
+    /// so errors about duplicate definitions point at it.
+    pub(crate) fn method_ident(&self, input: &InputEnum, template: &str, i: usize) -> Ident {
+        let mut words = vec![];
+        for word in template.split('_') {
+            match word {
+                "{}" => words.extend(input.variant_words(i)),
+                _ => words.push(word.to_string()),
+            }
+        }
+        let span = input.variant(i).ident.span();
+        case::ident(&self.rename_rule().apply(&words), span)
+    }
+
+    /// Name of the function `name`, which does not depend on a variant.
+    pub(crate) fn function_ident(&self, name: &str) -> Ident {
+        let words: Vec<String> = name.split('_').map(String::from).collect();
+        case::ident(&self.rename_rule().apply(&words), Span::call_site())
+    }
+
+    /// Name of an enum wide function which can be renamed with `name = ...`, like `as_ref`.
+    pub(crate) fn renamed_function_ident(&self, fc: &FunctionConfig, name: &str) -> Ident {
+        fc.name.clone().unwrap_or_else(|| self.function_ident(name))
+    }
+
+    /// `#[allow(non_snake_case)]` for impl blocks, if `rename_all` selects such a case style.
+    pub(crate) fn non_snake_case_attribute(&self) -> Option<TokenStream> {
+        (!self.rename_rule().is_snake_case()).then(|| quote! { #[allow(non_snake_case)] })
+    }
+
+    fn rename_rule(&self) -> RenameRule {
+        self.rename_all.unwrap_or_default()
+    }
+
+    fn parse_option(
+        &mut self,
+        meta: &ParseNestedMeta,
+        enabled: &mut Vec<&'static str>,
+    ) -> syn::Result<()> {
+        let Some(ident) = meta.path.get_ident() else {
+            return Err(meta.error("expected an option name"));
+        };
+        let name = ident.to_string();
+
+        if name == "helpers_vis" {
+            return set_once(&mut self.helpers_vis, meta.value()?.parse()?, meta);
+        }
+        if name == "rename_all" {
+            let lit: LitStr = meta.value()?.parse()?;
+            let rule = RenameRule::parse(&lit)?;
+            if !rule.is_identifier() {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    format!("`{}` can not be used for function names", lit.value()),
+                ));
+            }
+            return set_once(&mut self.rename_all, rule, meta);
+        }
+        if let Some((_, options)) = PRESETS.iter().find(|(preset, _)| *preset == name) {
+            if !meta.input.is_empty() && !meta.input.peek(Token![,]) {
+                return Err(meta.error("presets do not take any arguments"));
+            }
+            enabled.extend(options.iter().filter(|option| !OPT_IN.contains(option)));
+            return Ok(());
+        }
+
+        match self.slot(&name) {
+            Some(slot) => slot.parse(meta),
+            None => Err(meta.error(format!("unknown option `{ident}`"))),
+        }
+    }
+
+    fn is_enabled(&mut self, name: &str) -> bool {
+        self.slot(name).is_some_and(|slot| slot.is_enabled())
+    }
+
+    fn disable(&mut self, name: &str) {
+        if let Some(slot) = self.slot(name) {
+            slot.disable();
+        }
+    }
+
+    /// Span of `name` if the option was given by name.
+    fn requested_span(&self, name: &str) -> Option<Span> {
+        let requested = self.requested.iter().find(|(option, _)| *option == name);
+        requested.map(|(_, span)| *span)
+    }
+
+    fn slot(&mut self, name: &str) -> Option<Slot<'_>> {
+        Some(match name {
+            "TagEnum" => Slot::TagEnum(&mut self.tag_enum),
+            "RefEnum" => Slot::RefEnum(&mut self.ref_enum),
+            "MutEnum" => Slot::RefEnum(&mut self.mut_enum),
+            "traits" => Slot::Traits(&mut self.traits),
+            "is" => Slot::Function(&mut self.is, &[]),
+            "unwrap" => Slot::Function(&mut self.unwrap, &["track_caller"]),
+            "unwrap_ref" => Slot::Function(&mut self.unwrap_ref, &["track_caller"]),
+            "unwrap_mut" => Slot::Function(&mut self.unwrap_mut, &["track_caller"]),
+            "to_tag" => Slot::Function(&mut self.to_tag, &[]),
+            "as_ref" => Slot::Function(&mut self.as_ref, &["name"]),
+            "as_mut" => Slot::Function(&mut self.as_mut, &["name"]),
+            "get" => Slot::Function(&mut self.get, &[]),
+            "get_ref" => Slot::Function(&mut self.get_ref, &[]),
+            "get_mut" => Slot::Function(&mut self.get_mut, &[]),
+            "into" => Slot::Function(&mut self.into, &[]),
+            "expect" => Slot::Function(&mut self.expect, &["track_caller"]),
+            "expect_ref" => Slot::Function(&mut self.expect_ref, &["track_caller"]),
+            "expect_mut" => Slot::Function(&mut self.expect_mut, &["track_caller"]),
+            "unwrap_or" => Slot::Function(&mut self.unwrap_or, &[]),
+            "unwrap_or_default" => Slot::Function(&mut self.unwrap_or_default, &[]),
+            "unwrap_ref_or" => Slot::Function(&mut self.unwrap_ref_or, &[]),
+            "unwrap_mut_or" => Slot::Function(&mut self.unwrap_mut_or, &[]),
+            "map" => Slot::Function(&mut self.map, &[]),
+            "update" => Slot::Function(&mut self.update, &[]),
+            "new" => Slot::Function(&mut self.new, &["into"]),
+            "take" => Slot::Function(&mut self.take, &[]),
+            "replace" => Slot::Function(&mut self.replace, &[]),
+            "insert" => Slot::Function(&mut self.insert, &[]),
+            "get_or_insert" => Slot::Function(&mut self.get_or_insert, &[]),
+            "fields" => Slot::Function(&mut self.fields, &[]),
+            "fields_mut" => Slot::Function(&mut self.fields_mut, &[]),
+            "shared_fields" => Slot::Function(&mut self.shared_fields, &[]),
+            "shared_fields_mut" => Slot::Function(&mut self.shared_fields_mut, &[]),
+            "metadata" => Slot::Function(&mut self.metadata, &[]),
+            "try_unwrap" => Slot::Function(&mut self.try_unwrap, &[]),
+            "from" => Slot::Function(&mut self.from, &["skip_ambiguous"]),
+            _ => return None,
+        })
+    }
+}
+
+/// Storage of a single option in the config. Functions list the flags they accept besides `vis`.
+enum Slot<'c> {
+    TagEnum(&'c mut Option<TagEnumConfig>),
+    RefEnum(&'c mut Option<RefEnumConfig>),
+    Traits(&'c mut Option<TraitsConfig>),
+    Function(&'c mut Option<FunctionConfig>, &'static [&'static str]),
+}
+
+impl Slot<'_> {
+    fn parse(self, meta: &ParseNestedMeta) -> syn::Result<()> {
+        match self {
+            Slot::TagEnum(slot) => set_once(slot, TagEnumConfig::parse(meta)?, meta),
+            Slot::RefEnum(slot) => set_once(slot, RefEnumConfig::parse(meta)?, meta),
+            Slot::Traits(slot) => set_once(slot, TraitsConfig::parse(meta)?, meta),
+            Slot::Function(slot, flags) => {
+                set_once(slot, FunctionConfig::parse(meta, flags)?, meta)
+            }
+        }
+    }
+
+    /// Enables the option with its default config, unless it is configured already.
+    fn enable(self) {
+        match self {
+            Slot::TagEnum(slot) => _ = slot.get_or_insert_with(Default::default),
+            Slot::RefEnum(slot) => _ = slot.get_or_insert_with(Default::default),
+            Slot::Traits(slot) => _ = slot.get_or_insert_with(Default::default),
+            Slot::Function(slot, _) => _ = slot.get_or_insert_with(Default::default),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        match self {
+            Slot::TagEnum(slot) => slot.is_some(),
+            Slot::RefEnum(slot) => slot.is_some(),
+            Slot::Traits(slot) => slot.is_some(),
+            Slot::Function(slot, _) => slot.is_some(),
+        }
+    }
+
+    fn disable(self) {
+        match self {
+            Slot::TagEnum(slot) => *slot = None,
+            Slot::RefEnum(slot) => *slot = None,
+            Slot::Traits(slot) => *slot = None,
+            Slot::Function(slot, _) => *slot = None,
+        }
+    }
+}
+
+impl VariantConfig {
+    /// Parses all `#[enum_helper(...)]` attributes in `attrs`.
+    pub(crate) fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut config = Self::default();
+        let mut diagnostics = Diagnostics::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident(HELPER_ATTRIBUTE)) {
+            let result = attr.parse_nested_meta(|meta| {
+                let result = config.parse_option(&meta);
+                recover(&meta, result, &mut diagnostics)
+            });
+            diagnostics.check(result);
+        }
+        diagnostics.finish().map(|()| config)
+    }
+
+    pub(crate) fn skips(&self, helper: &str) -> bool {
+        self.skip_all || self.skip.iter().any(|s| s == helper)
+    }
+
+    fn parse_option(&mut self, meta: &ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("skip") {
+            if !meta.input.peek(token::Paren) {
+                self.skip_all = true;
+                return Ok(());
+            }
+            let skip = &mut self.skip;
+            parse_nested(meta, |meta| {
+                let helper = meta.path.get_ident().map(|i| i.to_string());
+                match helper {
+                    Some(helper) if VARIANT_HELPERS.contains(&helper.as_str()) => {
+                        skip.push(helper);
+                        Ok(())
+                    }
+                    _ => Err(meta.error(format!(
+                        "expected one of the per-variant helpers: {}",
+                        VARIANT_HELPERS.join(", ")
+                    ))),
+                }
+            })
+        } else if meta.path.is_ident("rename") {
+            self.rename = Some(parse_rename(meta)?);
+            Ok(())
+        } else {
+            Err(meta.error("unknown variant option, expected `skip` or `rename`"))
+        }
+    }
+}
+
+impl TagEnumConfig {
+    fn parse(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config = Self::default();
+        parse_nested(meta, |meta| {
+            if meta.path.is_ident("name") {
+                config.name = Some(parse_name(meta)?);
+                Ok(())
+            } else if meta.path.is_ident("vis") {
+                config.vis = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("derive") {
+                config.derives.extend(parse_derives(meta)?);
+                Ok(())
+            } else if meta.path.is_ident("strings") {
+                set_once(&mut config.strings, TagStringsConfig::parse(meta)?, meta)
+            } else {
+                Err(meta
+                    .error("unknown TagEnum option, expected `name`, `vis`, `derive` or `strings`"))
+            }
+        })?;
+        Ok(config)
+    }
+}
+
+impl TagStringsConfig {
+    fn parse(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config = Self::default();
+        parse_nested(meta, |meta| {
+            if meta.path.is_ident("rename_all") {
+                let rule = RenameRule::parse(&meta.value()?.parse()?)?;
+                set_once(&mut config.rename_all, rule, meta)
+            } else if meta.path.is_ident("case_insensitive") {
+                config.case_insensitive = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown option, expected `rename_all` or `case_insensitive`"))
+            }
+        })?;
+        Ok(config)
+    }
+}
+
+impl RefEnumConfig {
+    fn parse(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config = Self::default();
+        parse_nested(meta, |meta| {
+            if meta.path.is_ident("name") {
+                config.name = Some(parse_name(meta)?);
+                Ok(())
+            } else if meta.path.is_ident("vis") {
+                config.vis = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("derive") {
+                config.derives.extend(parse_derives(meta)?);
+                Ok(())
+            } else if meta.path.is_ident("lifetime") {
+                config.lifetime = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("from") {
+                config.from = true;
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "unknown option, expected `name`, `vis`, `derive`, `lifetime` or `from`",
+                ))
+            }
+        })?;
+        Ok(config)
+    }
+}
+
+impl TraitsConfig {
+    fn parse(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut config = Self::default();
+        parse_nested(meta, |meta| {
+            if meta.path.is_ident("crate") {
+                config.path = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown option, expected `crate`"))
+            }
+        })?;
+        Ok(config)
+    }
+}
+
+impl FunctionConfig {
+    fn parse(meta: &ParseNestedMeta, flags: &[&str]) -> syn::Result<Self> {
+        let mut config = Self::default();
+        parse_nested(meta, |meta| {
+            let flag = meta.path.get_ident().map(|i| i.to_string());
+            match flag.as_deref() {
+                Some("vis") => config.vis = Some(meta.value()?.parse()?),
+                Some(flag) if !flags.contains(&flag) => {
+                    let expected = flags
+                        .iter()
+                        .map(|f| format!(" or `{f}`"))
+                        .collect::<String>();
+                    return Err(meta.error(format!("unknown option, expected `vis`{expected}")));
+                }
+                // Panicking helpers always have `#[track_caller]`, the flag is still accepted.
+                Some("track_caller") => {}
+                Some("into") => config.into = true,
+                Some("name") => config.name = Some(parse_name(meta)?),
+                Some("skip_ambiguous") => config.skip_ambiguous = true,
+                _ => return Err(meta.error("unknown option, expected `vis`")),
+            }
+            Ok(())
+        })?;
+        Ok(config)
+    }
+}
+
+fn default_ident(input: &InputEnum, suffix: &str) -> Ident {
+    Ident::new(
+        format!("{}{suffix}", input.name()).as_str(),
+        Span::call_site(),
+    )
+}
+
+/// Parses `= Name` or `= "Name"`.
+fn parse_name(meta: &ParseNestedMeta) -> syn::Result<Ident> {
+    let value = meta.value()?;
+    if value.peek(LitStr) {
+        value.parse::<LitStr>()?.parse()
+    } else {
+        value.parse()
+    }
+}
+
+/// Parses `= name` or `= "name"`. Keywords are allowed since the name is only used as part of
+/// function names.
+fn parse_rename(meta: &ParseNestedMeta) -> syn::Result<String> {
+    let value = meta.value()?;
+    let (name, span) = if value.peek(LitStr) {
+        let lit = value.parse::<LitStr>()?;
+        (lit.value(), lit.span())
+    } else {
+        let ident = value.call(Ident::parse_any)?;
+        (ident.unraw().to_string(), ident.span())
+    };
+
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(syn::Error::new(
+            span,
+            "expected a name consisting of alphanumeric characters and `_`",
+        ));
+    }
+    // Some generated names start with the variant name, e.g. the field accessors.
+    if name.starts_with(|c: char| c.is_numeric()) {
+        return Err(syn::Error::new(
+            span,
+            "expected a name not starting with a digit",
+        ));
+    }
+    Ok(name)
+}
+
+/// Parses `derive(A, B, ...)`.
+fn parse_derives(meta: &ParseNestedMeta) -> syn::Result<Punctuated<Path, Token![,]>> {
+    let content;
+    parenthesized!(content in meta.input);
+    Punctuated::parse_terminated(&content)
+}
+
+/// Splits `tokens` at top level commas. The `!` of excluded options is returned separately.
+fn split_options(tokens: TokenStream) -> Vec<(Option<Span>, TokenStream)> {
+    let mut options = vec![];
+    let mut bang = None;
+    let mut option = TokenStream::new();
+    for tt in tokens {
+        match &tt {
+            TokenTree::Punct(p) if p.as_char() == ',' => {
+                options.push((bang.take(), std::mem::take(&mut option)));
+            }
+            TokenTree::Punct(p) if p.as_char() == '!' && option.is_empty() && bang.is_none() => {
+                bang = Some(p.span());
+            }
+            _ => option.extend([tt]),
+        }
+    }
+    if bang.is_some() || !option.is_empty() {
+        options.push((bang, option));
+    }
+    options
+}
+
+/// Parses the name after `!`. Returns all options it refers to.
+fn parse_exclusion(meta: &ParseNestedMeta) -> syn::Result<&'static [&'static str]> {
+    if !meta.input.is_empty() {
+        return Err(meta.error("excluded options do not take any arguments"));
+    }
+
+    let name = meta
+        .path
+        .get_ident()
+        .map(|i| i.to_string())
+        .unwrap_or_default();
+    match expand_option(&name) {
+        [] => Err(meta.error(format!("unknown option `{name}`"))),
+        options => Ok(options),
+    }
+}
+
+/// All options enabled by `name`, which is either a preset or a single option.
+fn expand_option(name: &str) -> &'static [&'static str] {
+    if let Some((_, options)) = PRESETS.iter().find(|(preset, _)| *preset == name) {
+        return options;
+    }
+    match OPTIONS.iter().position(|option| *option == name) {
+        Some(i) => &OPTIONS[i..=i],
+        None => &[],
+    }
+}
+
+/// Runs `logic` on the parenthesized arguments of `meta`, if there are any.
+fn parse_nested(
+    meta: &ParseNestedMeta,
+    mut logic: impl FnMut(&ParseNestedMeta) -> syn::Result<()>,
+) -> syn::Result<()> {
+    if !meta.input.peek(token::Paren) {
+        return Ok(());
+    }
+
+    let mut diagnostics = Diagnostics::default();
+    let result = meta.parse_nested_meta(|meta| {
+        let result = logic(&meta);
+        recover(&meta, result, &mut diagnostics)
+    });
+    diagnostics.check(result);
+    diagnostics.finish()
+}
+
+/// Records a failed option and skips its remaining arguments, so parsing continues with the
+/// next option.
+fn recover(
+    meta: &ParseNestedMeta,
+    result: syn::Result<()>,
+    diagnostics: &mut Diagnostics,
+) -> syn::Result<()> {
+    if let Err(error) = result {
+        diagnostics.push(error);
+        while !meta.input.is_empty() && !meta.input.peek(Token![,]) {
+            meta.input.parse::<TokenTree>()?;
+        }
+    }
+    Ok(())
+}
+
+fn set_once<T>(slot: &mut Option<T>, value: T, meta: &ParseNestedMeta) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(meta.error("option is specified more than once"));
+    }
+    *slot = Some(value);
+    Ok(())
+}
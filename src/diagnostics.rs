@@ -0,0 +1,36 @@
+/// Collects errors so that several mistakes are reported in a single compilation.
+#[derive(Default)]
+pub(crate) struct Diagnostics {
+    error: Option<syn::Error>,
+}
+
+impl Diagnostics {
+    pub(crate) fn push(&mut self, error: syn::Error) {
+        match &mut self.error {
+            Some(existing) => existing.combine(error),
+            None => self.error = Some(error),
+        }
+    }
+
+    /// Records the error of `result`, if any. Returns the value otherwise.
+    pub(crate) fn check<T>(&mut self, result: syn::Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.push(error);
+                None
+            }
+        }
+    }
+
+    pub(crate) fn has_errors(&self) -> bool {
+        self.error.is_some()
+    }
+
+    pub(crate) fn finish(self) -> syn::Result<()> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
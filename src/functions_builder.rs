@@ -1,30 +1,159 @@
-use crate::{parse_function, Ident, InputEnum, Span};
+use crate::{
+    case,
+    config::{Config, FunctionConfig},
+    diagnostics::Diagnostics,
+    metadata_items, parse_function, respan, same_type, Ident, InputEnum, Span,
+};
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{
-    punctuated::Punctuated,
+    ext::IdentExt,
+    spanned::Spanned,
     token::{self},
-    Arm, Expr, FieldValue, Fields, ItemFn, Lifetime, Type,
+    Arm, Expr, FieldValue, Fields, ImplItemConst, ItemFn, ItemImpl, Lifetime,
 };
 
+/// How a generated function takes `self`.
+#[derive(Clone, Copy)]
+enum Receiver {
+    Owned,
+    Ref,
+    Mut,
+}
+
 pub(crate) struct EnumFunctionsBuilder<'a> {
     input: &'a InputEnum,
+    config: &'a Config,
     functions: Vec<ItemFn>,
+    constants: Vec<ImplItemConst>,
+    /// Trait implementations, emitted next to the inherent impl block.
+    impls: Vec<ItemImpl>,
+    /// Types emitted next to the inherent impl block, like the error of `try_unwrap`.
+    types: Vec<proc_macro2::TokenStream>,
 }
 
 impl<'a> EnumFunctionsBuilder<'a> {
-    pub(crate) fn new(input: &'a InputEnum) -> Self {
+    pub(crate) fn new(input: &'a InputEnum, config: &'a Config) -> Self {
         Self {
             input,
+            config,
             functions: vec![],
+            constants: vec![],
+            impls: vec![],
+            types: vec![],
         }
     }
 
-    pub(crate) fn is_functions(&mut self) {
-        let vs = self.input.vis();
-        for i in 0..self.input.variant_count() {
-            let nm = self.input.variant_snake_case_name(i);
-            let sp = Ident::new(format!("is_{nm}").as_str(), Span::call_site());
+    /// Generates all functions requested in the config.
+    pub(crate) fn generate(&mut self) -> syn::Result<()> {
+        let config = self.config;
+        let mut diagnostics = Diagnostics::default();
+        if let Some(fc) = &config.is {
+            diagnostics.check(self.is_functions(fc));
+        }
+        if let Some(fc) = &config.unwrap {
+            diagnostics.check(self.unwrap_functions(fc));
+        }
+        if let Some(fc) = &config.unwrap_ref {
+            diagnostics.check(self.unwrap_ref_functions(fc));
+        }
+        if let Some(fc) = &config.unwrap_mut {
+            diagnostics.check(self.unwrap_mut_functions(fc));
+        }
+        if let Some(fc) = &config.to_tag {
+            diagnostics.check(self.to_tag_function(fc));
+        }
+        if let Some(fc) = &config.as_ref {
+            diagnostics.check(self.as_ref_functions(fc));
+        }
+        if let Some(fc) = &config.as_mut {
+            diagnostics.check(self.as_mut_functions(fc));
+        }
+        if let Some(fc) = &config.get {
+            diagnostics.check(self.get_functions(fc));
+        }
+        if let Some(fc) = &config.get_ref {
+            diagnostics.check(self.get_ref_functions(fc));
+        }
+        if let Some(fc) = &config.get_mut {
+            diagnostics.check(self.get_mut_functions(fc));
+        }
+        if let Some(fc) = &config.into {
+            diagnostics.check(self.owned_into_functions(fc));
+        }
+        if let Some(fc) = &config.expect {
+            diagnostics.check(self.expect_functions(fc));
+        }
+        if let Some(fc) = &config.expect_ref {
+            diagnostics.check(self.expect_ref_functions(fc));
+        }
+        if let Some(fc) = &config.expect_mut {
+            diagnostics.check(self.expect_mut_functions(fc));
+        }
+        if let Some(fc) = &config.unwrap_or {
+            diagnostics.check(self.unwrap_or_functions(fc));
+        }
+        if let Some(fc) = &config.unwrap_or_default {
+            diagnostics.check(self.unwrap_or_default_functions(fc));
+        }
+        if let Some(fc) = &config.unwrap_ref_or {
+            diagnostics.check(self.unwrap_ref_or_functions(fc));
+        }
+        if let Some(fc) = &config.unwrap_mut_or {
+            diagnostics.check(self.unwrap_mut_or_functions(fc));
+        }
+        if let Some(fc) = &config.map {
+            diagnostics.check(self.map_functions(fc));
+        }
+        if let Some(fc) = &config.update {
+            diagnostics.check(self.update_functions(fc));
+        }
+        if let Some(fc) = &config.new {
+            diagnostics.check(self.new_functions(fc));
+        }
+        if let Some(fc) = &config.take {
+            diagnostics.check(self.take_functions(fc));
+        }
+        if let Some(fc) = &config.replace {
+            diagnostics.check(self.replace_functions(fc));
+        }
+        if let Some(fc) = &config.insert {
+            diagnostics.check(self.insert_functions(fc));
+        }
+        if let Some(fc) = &config.get_or_insert {
+            diagnostics.check(self.get_or_insert_functions(fc));
+        }
+        if let Some(fc) = &config.fields {
+            diagnostics.check(self.fields_functions(fc));
+        }
+        if let Some(fc) = &config.fields_mut {
+            diagnostics.check(self.fields_mut_functions(fc));
+        }
+        if let Some(fc) = &config.shared_fields {
+            diagnostics.check(self.shared_fields_functions(fc));
+        }
+        if let Some(fc) = &config.shared_fields_mut {
+            diagnostics.check(self.shared_fields_mut_functions(fc));
+        }
+        if let Some(fc) = &config.metadata {
+            diagnostics.check(self.metadata_functions(fc));
+        }
+        if let Some(fc) = &config.try_unwrap {
+            diagnostics.check(self.try_unwrap_functions(fc));
+        }
+        if let Some(fc) = &config.from {
+            diagnostics.check(self.payload_from_impls(fc));
+        }
+        if config.traits.is_some() {
+            diagnostics.check(self.trait_impls());
+        }
+        diagnostics.finish()
+    }
+
+    pub(crate) fn is_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self.input.helper_variants("is") {
+            let sp = self.config.method_ident(self.input, "is_{}", i);
             let pat = self.input.match_variant(i, None);
             let ts = quote! {
                 #vs fn #sp (&self) -> bool {
@@ -32,173 +161,648 @@ impl<'a> EnumFunctionsBuilder<'a> {
                 }
             };
 
-            let mut ifn = None;
-            parse_function(ts, &mut ifn);
-
-            if let Some(ifn) = ifn {
-                self.functions.push(ifn);
-            } else {
-                panic!()
-            }
+            self.functions.push(parse_function(ts)?);
         }
+        Ok(())
     }
 
-    pub(crate) fn unwrap_functions(&mut self) {
-        let vs = self.input.vis();
-        for i in 0..self.input.variant_count() {
-            let nm = self.input.variant_snake_case_name(i);
-            let sp = Ident::new(format!("unwrap_{nm}").as_str(), Span::call_site());
+    pub(crate) fn unwrap_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        self.panicking_functions(fc, "unwrap", Receiver::Owned)
+    }
+
+    pub(crate) fn unwrap_ref_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        self.panicking_functions(fc, "unwrap_ref", Receiver::Ref)
+    }
+
+    pub(crate) fn unwrap_mut_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        self.panicking_functions(fc, "unwrap_mut", Receiver::Mut)
+    }
+
+    pub(crate) fn expect_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        self.panicking_functions(fc, "expect", Receiver::Owned)
+    }
+
+    pub(crate) fn expect_ref_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        self.panicking_functions(fc, "expect_ref", Receiver::Ref)
+    }
+
+    pub(crate) fn expect_mut_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        self.panicking_functions(fc, "expect_mut", Receiver::Mut)
+    }
+
+    /// Generates the `unwrap` or `expect` functions of `helper`. They return the fields of their
+    /// variant and panic for all other variants.
+    fn panicking_functions(
+        &mut self,
+        fc: &FunctionConfig,
+        helper: &str,
+        receiver: Receiver,
+    ) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        let expect = helper.starts_with("expect");
+        let msg = expect.then(|| quote! { , msg: &str });
+        for i in self.input.helper_variants(helper) {
+            let sp = self
+                .config
+                .method_ident(self.input, &format!("{helper}_{{}}"), i);
             let arm = self.input.match_variant_to_tuple(i, None);
+            let panic = self.input.wrong_variant_panic(&sp, None, expect);
+
+            let (receiver, return_type) = match receiver {
+                Receiver::Owned => (quote! { self }, self.input.variant_type(i)),
+                Receiver::Ref => (
+                    quote! { &self },
+                    self.input.variant_ref_type(i, false, None),
+                ),
+                Receiver::Mut => (
+                    quote! { &mut self },
+                    self.input.variant_ref_type(i, true, None),
+                ),
+            };
+            let ts = quote! {
+                #[track_caller]
+                #vs fn #sp (#receiver #msg) -> #return_type {
+                    match self {
+                        #arm
+                        _ => #panic
+                    }
+                }
+            };
+
+            self.functions.push(parse_function(ts)?);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn unwrap_or_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        self.fallback_functions(fc, "unwrap", Receiver::Owned)
+    }
+
+    pub(crate) fn unwrap_ref_or_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        self.fallback_functions(fc, "unwrap_ref", Receiver::Ref)
+    }
+
+    pub(crate) fn unwrap_mut_or_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        self.fallback_functions(fc, "unwrap_mut", Receiver::Mut)
+    }
+
+    /// Generates the `{family}_{}_or` and `{family}_{}_or_else` functions. They return the
+    /// fields of their variant and the given fallback for all other variants. Variants without
+    /// fields have nothing to return and are skipped.
+    fn fallback_functions(
+        &mut self,
+        fc: &FunctionConfig,
+        family: &str,
+        receiver: Receiver,
+    ) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        let helper = format!("{family}_or");
+        let lifetime = Lifetime::new("'reb", Span::call_site());
+        for i in self
+            .input
+            .helper_variants(&helper)
+            .filter(|i| self.input.has_fields(*i))
+        {
+            let or = self
+                .config
+                .method_ident(self.input, &format!("{family}_{{}}_or"), i);
+            let or_else =
+                self.config
+                    .method_ident(self.input, &format!("{family}_{{}}_or_else"), i);
+            let syn::Arm { pat, body, .. } = self.input.match_variant_to_tuple(i, None);
+
+            let (generics, receiver, return_type) = match receiver {
+                Receiver::Owned => (None, quote! { self }, self.input.variant_type(i)),
+                Receiver::Ref => (
+                    Some(quote! { <#lifetime> }),
+                    quote! { & #lifetime self },
+                    self.input.variant_ref_type(i, false, Some(&lifetime)),
+                ),
+                Receiver::Mut => (
+                    Some(quote! { <#lifetime> }),
+                    quote! { & #lifetime mut self },
+                    self.input.variant_ref_type(i, true, Some(&lifetime)),
+                ),
+            };
+            let ts = quote! {
+                #vs fn #or #generics (#receiver, default: #return_type) -> #return_type {
+                    match self {
+                        #pat => { #body },
+                        _ => default
+                    }
+                }
+            };
+            self.functions.push(parse_function(ts)?);
+
+            let ts = quote! {
+                #vs fn #or_else #generics (
+                    #receiver,
+                    f: impl FnOnce() -> #return_type,
+                ) -> #return_type {
+                    match self {
+                        #pat => { #body },
+                        _ => f()
+                    }
+                }
+            };
+            self.functions.push(parse_function(ts)?);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn unwrap_or_default_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self.input.helper_variants("unwrap_or_default") {
+            let sp = self
+                .config
+                .method_ident(self.input, "unwrap_{}_or_default", i);
+            let syn::Arm { pat, body, .. } = self.input.match_variant_to_tuple(i, None);
 
             let return_type = self.input.variant_type(i);
             let ts = quote! {
                 #vs fn #sp (self) -> #return_type {
                     match self {
-                        #arm
-                        _ => panic!()
+                        #pat => { #body },
+                        _ => Default::default()
                     }
                 }
             };
 
-            let mut ufn = None;
-            parse_function(ts, &mut ufn);
+            self.functions.push(parse_function(ts)?);
+        }
+        Ok(())
+    }
 
-            if let Some(ufn) = ufn {
-                self.functions.push(ufn);
-            } else {
-                panic!()
+    pub(crate) fn map_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self
+            .input
+            .helper_variants("map")
+            .filter(|i| self.input.has_fields(*i))
+        {
+            let sp = self.config.method_ident(self.input, "map_{}", i);
+            let syn::Arm { pat, body, .. } = self.input.match_variant_to_tuple(i, None);
+            let construct = self.input.construct_variant(i, None, quote! { f(value) });
+
+            // The closure is called outside of the match, where no field can shadow it.
+            let return_type = self.input.variant_type(i);
+            let ts = quote! {
+                #vs fn #sp (self, f: impl FnOnce(#return_type) -> #return_type) -> Self {
+                    let value = match self {
+                        #pat => { #body },
+                        other => return other
+                    };
+                    #construct
+                }
+            };
+
+            self.functions.push(parse_function(ts)?);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn update_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self
+            .input
+            .helper_variants("update")
+            .filter(|i| self.input.has_fields(*i))
+        {
+            let sp = self.config.method_ident(self.input, "update_{}", i);
+            let syn::Arm { pat, body, .. } = self.input.match_variant_to_tuple(i, None);
+
+            let rt = self.input.variant_ref_type(i, true, None);
+            let ts = quote! {
+                #vs fn #sp (&mut self, f: impl FnOnce(#rt)) {
+                    let value = match self {
+                        #pat => { #body },
+                        _ => return
+                    };
+                    f(value)
+                }
+            };
+
+            self.functions.push(parse_function(ts)?);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn new_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self.input.helper_variants("new") {
+            let sp = self.config.method_ident(self.input, "new_{}", i);
+            let variant = self.input.variant(i);
+            let variant_name = &variant.ident;
+
+            let mut arguments = vec![];
+            let mut values = vec![];
+            for (index, field) in variant.fields.iter().enumerate() {
+                let name = match &field.ident {
+                    Some(ident) => ident.clone(),
+                    None => Ident::new(&format!("e{index}"), Span::call_site()),
+                };
+                let ty = &field.ty;
+                if fc.into {
+                    arguments.push(quote! { #name: impl Into<#ty> });
+                    values.push(quote! { #name.into() });
+                } else {
+                    arguments.push(quote! { #name: #ty });
+                    values.push(quote! { #name });
+                }
             }
+
+            let construct = match &variant.fields {
+                Fields::Unit => quote! { Self :: #variant_name },
+                Fields::Unnamed(_) => quote! { Self :: #variant_name ( #(#values),* ) },
+                Fields::Named(fields) => {
+                    let names = fields.named.iter().map(|f| &f.ident);
+                    quote! { Self :: #variant_name { #(#names: #values),* } }
+                }
+            };
+            let ts = quote! {
+                #vs fn #sp ( #(#arguments),* ) -> Self {
+                    #construct
+                }
+            };
+
+            self.functions.push(parse_function(ts)?);
         }
+        Ok(())
     }
 
-    pub(crate) fn unwrap_ref_functions(&mut self) {
-        let vs = self.input.vis();
-        for i in 0..self.input.variant_count() {
-            let nm = self.input.variant_snake_case_name(i);
-            let sp = Ident::new(format!("unwrap_ref_{nm}").as_str(), Span::call_site());
-            let arm = self.input.match_variant_to_tuple(i, None);
+    pub(crate) fn take_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self.input.helper_variants("take") {
+            let sp = self.config.method_ident(self.input, "take_{}", i);
+            let wild = self.input.match_variant(i, None);
+            let syn::Arm { pat, body, .. } = self.input.match_variant_to_tuple(i, None);
 
             let return_type = self.input.variant_type(i);
+            let ts = quote! {
+                #vs fn #sp (&mut self, replacement: Self) -> Option<#return_type> {
+                    if !matches!(self, #wild) {
+                        return None;
+                    }
+                    match ::core::mem::replace(self, replacement) {
+                        #pat => { Some ( #body ) },
+                        _ => unreachable!()
+                    }
+                }
+            };
 
-            let rt = match return_type {
-                ty @ Type::Path(_) => Type::Reference(syn::TypeReference {
-                    and_token: token::And {
-                        spans: [Span::call_site(); 1],
-                    },
-                    lifetime: None,
-                    mutability: None,
-                    elem: Box::new(ty),
-                }),
-                Type::Tuple(tuple) => Type::Tuple(syn::TypeTuple {
-                    paren_token: tuple.paren_token,
-                    elems: tuple
-                        .elems
-                        .into_iter()
-                        .map(|t| {
-                            Type::Reference(syn::TypeReference {
-                                and_token: token::And {
-                                    spans: [Span::call_site(); 1],
-                                },
-                                lifetime: None,
-                                mutability: None,
-                                elem: Box::new(t),
-                            })
-                        })
-                        .collect(),
-                }),
-                _ => panic!(),
+            self.functions.push(parse_function(ts)?);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn replace_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self
+            .input
+            .helper_variants("replace")
+            .filter(|i| self.input.has_fields(*i))
+        {
+            let sp = self.config.method_ident(self.input, "replace_{}", i);
+            let wild = self.input.match_variant(i, None);
+            let syn::Arm { pat, body, .. } = self.input.match_variant_to_tuple(i, None);
+            let construct = self.input.construct_variant(i, None, quote! { new });
+
+            let return_type = self.input.variant_type(i);
+            let ts = quote! {
+                #vs fn #sp (&mut self, new: #return_type) -> Option<#return_type> {
+                    if !matches!(self, #wild) {
+                        return None;
+                    }
+                    match ::core::mem::replace(self, #construct) {
+                        #pat => { Some ( #body ) },
+                        _ => unreachable!()
+                    }
+                }
             };
 
+            self.functions.push(parse_function(ts)?);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn insert_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self
+            .input
+            .helper_variants("insert")
+            .filter(|i| self.input.has_fields(*i))
+        {
+            let sp = self.config.method_ident(self.input, "insert_{}", i);
+            let construct = self.input.construct_variant(i, None, quote! { value });
+            let arm = self.input.match_variant_to_tuple(i, None);
+
+            let return_type = self.input.variant_type(i);
+            let rt = self.input.variant_ref_type(i, true, None);
             let ts = quote! {
-                #vs fn #sp (&self) -> #rt {
+                #vs fn #sp (&mut self, value: #return_type) -> #rt {
+                    *self = #construct;
                     match self {
                         #arm
-                        _ => panic!()
+                        _ => unreachable!()
                     }
                 }
             };
 
-            let mut ufn = None;
-            parse_function(ts, &mut ufn);
-
-            if let Some(ufn) = ufn {
-                self.functions.push(ufn);
-            } else {
-                panic!()
-            }
+            self.functions.push(parse_function(ts)?);
         }
+        Ok(())
     }
 
-    pub(crate) fn unwrap_mut_functions(&mut self) {
-        let vs = self.input.vis();
-        for i in 0..self.input.variant_count() {
-            let nm = self.input.variant_snake_case_name(i);
-            let sp = Ident::new(format!("unwrap_mut_{nm}").as_str(), Span::call_site());
+    pub(crate) fn get_or_insert_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self
+            .input
+            .helper_variants("get_or_insert")
+            .filter(|i| self.input.has_fields(*i))
+        {
+            let wild = self.input.match_variant(i, None);
             let arm = self.input.match_variant_to_tuple(i, None);
-
             let return_type = self.input.variant_type(i);
-            let mutability = Some(token::Mut {
-                span: Span::call_site(),
-            });
+            let rt = self.input.variant_ref_type(i, true, None);
 
-            let rt = match return_type {
-                ty @ Type::Path(_) => Type::Reference(syn::TypeReference {
-                    and_token: token::And {
-                        spans: [Span::call_site(); 1],
-                    },
-                    lifetime: None,
-                    mutability,
-                    elem: Box::new(ty),
-                }),
-                Type::Tuple(tuple) => Type::Tuple(syn::TypeTuple {
-                    paren_token: tuple.paren_token,
-                    elems: tuple
-                        .elems
-                        .into_iter()
-                        .map(|t| {
-                            Type::Reference(syn::TypeReference {
-                                and_token: token::And {
-                                    spans: [Span::call_site(); 1],
-                                },
-                                lifetime: None,
-                                mutability,
-                                elem: Box::new(t),
-                            })
-                        })
-                        .collect(),
-                }),
-                _ => panic!("Unexpected type."),
+            let sp = self.config.method_ident(self.input, "get_or_insert_{}", i);
+            let construct = self.input.construct_variant(i, None, quote! { value });
+            let ts = quote! {
+                #vs fn #sp (&mut self, value: #return_type) -> #rt {
+                    if !matches!(self, #wild) {
+                        *self = #construct;
+                    }
+                    match self {
+                        #arm
+                        _ => unreachable!()
+                    }
+                }
             };
+            self.functions.push(parse_function(ts)?);
 
+            let sp = self
+                .config
+                .method_ident(self.input, "get_or_insert_{}_with", i);
+            let construct = self.input.construct_variant(i, None, quote! { f() });
             let ts = quote! {
-                #vs fn #sp (&mut self) -> #rt {
+                #vs fn #sp (&mut self, f: impl FnOnce() -> #return_type) -> #rt {
+                    if !matches!(self, #wild) {
+                        *self = #construct;
+                    }
                     match self {
                         #arm
-                        _ => panic!()
+                        _ => unreachable!()
                     }
                 }
             };
+            self.functions.push(parse_function(ts)?);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn fields_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        self.field_accessors(fc, "fields", false)
+    }
+
+    pub(crate) fn fields_mut_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        self.field_accessors(fc, "fields_mut", true)
+    }
+
+    /// Generates `{variant}_{field}` or `{variant}_{field}_mut` for every field of variants with
+    /// named fields.
+    fn field_accessors(
+        &mut self,
+        fc: &FunctionConfig,
+        helper: &str,
+        mutable: bool,
+    ) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        let enum_name = &self.input.item.ident;
+        for i in self.input.helper_variants(helper) {
+            let variant_name = &self.input.variant(i).ident;
+            let Fields::Named(fields) = &self.input.variant(i).fields else {
+                continue;
+            };
+
+            for field in &fields.named {
+                let Some(field_name) = &field.ident else {
+                    continue;
+                };
+                let mut template = format!(
+                    "{{}}_{}",
+                    case::words(&field_name.unraw().to_string()).join("_")
+                );
+                if mutable {
+                    template += "_mut";
+                }
+                let sp = self.config.method_ident(self.input, &template, i);
+
+                let ty = &field.ty;
+                let (receiver, rt) = if mutable {
+                    (quote! { &mut self }, quote! { &mut #ty })
+                } else {
+                    (quote! { &self }, quote! { &#ty })
+                };
+                let ts = quote! {
+                    #vs fn #sp (#receiver) -> Option<#rt> {
+                        match self {
+                            #enum_name :: #variant_name { #field_name: value, .. } => Some(value),
+                            _ => None
+                        }
+                    }
+                };
+
+                self.functions.push(parse_function(ts)?);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn shared_fields_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        self.shared_field_accessors(fc, false)
+    }
+
+    pub(crate) fn shared_fields_mut_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        self.shared_field_accessors(fc, true)
+    }
+
+    /// Generates `{field}` or `{field}_mut` for fields shared between variants. The accessors
+    /// return an `Option` unless all variants have the field.
+    fn shared_field_accessors(&mut self, fc: &FunctionConfig, mutable: bool) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        let enum_name = &self.input.item.ident;
+        for field in self.input.shared_fields() {
+            let mut name = case::words(&field.ident.unraw().to_string()).join("_");
+            if mutable {
+                name += "_mut";
+            }
+            let sp = self.config.function_ident(&name);
 
-            let mut ufn = None;
-            parse_function(ts, &mut ufn);
+            let field_name = field.ident;
+            let variant_names = field.variants.iter().map(|i| &self.input.variant(*i).ident);
+            let pat = quote! {
+                #( #enum_name :: #variant_names { #field_name: value, .. } )|*
+            };
 
-            if let Some(ufn) = ufn {
-                self.functions.push(ufn);
+            let ty = field.ty;
+            let (receiver, rt) = if mutable {
+                (quote! { &mut self }, quote! { &mut #ty })
+            } else {
+                (quote! { &self }, quote! { &#ty })
+            };
+            let ts = if field.variants.len() == self.input.variant_count() {
+                quote! {
+                    #vs fn #sp (#receiver) -> #rt {
+                        match self {
+                            #pat => value
+                        }
+                    }
+                }
             } else {
-                panic!()
+                quote! {
+                    #vs fn #sp (#receiver) -> Option<#rt> {
+                        match self {
+                            #pat => Some(value),
+                            _ => None
+                        }
+                    }
+                }
+            };
+
+            self.functions.push(parse_function(ts)?);
+        }
+        Ok(())
+    }
+
+    /// Generates the `try_unwrap` functions and their error. The error identifies variants by
+    /// their tag if the TagEnum is generated and by their name otherwise.
+    pub(crate) fn try_unwrap_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        let error_ident = self.config.wrong_variant_ident(self.input);
+        let (_, type_g, _) = self.input.generics().split_for_impl();
+        let tag_ident = self
+            .config
+            .tag_enum
+            .as_ref()
+            .map(|_| self.config.tag_enum_ident(self.input));
+        let variant_id = |i: usize| {
+            let variant_ident = &self.input.variant(i).ident;
+            match &tag_ident {
+                Some(tag_ident) => quote! { #tag_ident :: #variant_ident },
+                None => {
+                    let name = variant_ident.unraw().to_string();
+                    quote! { #name }
+                }
+            }
+        };
+        let actual = self.input.variant_match(None, variant_id);
+        for i in self.input.helper_variants("try_unwrap") {
+            let sp = self.config.method_ident(self.input, "try_unwrap_{}", i);
+            let syn::Arm { pat, body, .. } = self.input.match_variant_to_tuple(i, None);
+            let expected = variant_id(i);
+
+            let return_type = self.input.variant_type(i);
+            let ts = quote! {
+                #vs fn #sp (self) -> Result<#return_type, #error_ident #type_g> {
+                    let actual = #actual;
+                    match self {
+                        #pat => { Ok ( #body ) },
+                        value => Err(#error_ident {
+                            expected: #expected,
+                            actual,
+                            value,
+                        })
+                    }
+                }
+            };
+
+            self.functions.push(parse_function(ts)?);
+        }
+
+        self.types
+            .push(self.wrong_variant_error(fc, tag_ident.as_ref()));
+        Ok(())
+    }
+
+    /// Error of the `try_unwrap` functions. It holds the expected and the actual variant as well
+    /// as the original value.
+    fn wrong_variant_error(
+        &self,
+        fc: &FunctionConfig,
+        tag_ident: Option<&Ident>,
+    ) -> proc_macro2::TokenStream {
+        let vs = self.config.function_vis(fc, self.input);
+        let enum_name = &self.input.item.ident;
+        let error_name = self.config.wrong_variant_ident(self.input);
+        let generics = self.input.generics();
+        let (impl_g, type_g, where_clause) = generics.split_for_impl();
+
+        let (variant_type, name) = match tag_ident {
+            Some(tag_ident) => {
+                let arms = self.input.iter_variants().map(|v| {
+                    let variant_name = &v.ident;
+                    let name = variant_name.unraw().to_string();
+                    quote! { #tag_ident :: #variant_name => #name }
+                });
+                let name = quote! {
+                    fn name(tag: #tag_ident) -> &'static str {
+                        match tag {
+                            #(#arms ,)*
+                        }
+                    }
+                };
+                (quote! { #tag_ident }, name)
+            }
+            None => {
+                let name = quote! {
+                    fn name(name: &'static str) -> &'static str {
+                        name
+                    }
+                };
+                (quote! { &'static str }, name)
+            }
+        };
+        let error_doc = format!("Error returned by the `try_unwrap` functions of [`{enum_name}`].");
+        let display = format!("expected {enum_name}::{{}}, found {enum_name}::{{}}");
+        let debug = error_name.to_string();
+        quote! {
+            #[doc = #error_doc]
+            #vs struct #error_name #generics #where_clause {
+                /// The variant the function was called for.
+                pub expected: #variant_type,
+                /// The variant of `value`.
+                pub actual: #variant_type,
+                /// The value the function was called on.
+                pub value: #enum_name #type_g,
+            }
+
+            impl #impl_g ::core::fmt::Display for #error_name #type_g #where_clause {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    #name
+                    write!(f, #display, name(self.expected), name(self.actual))
+                }
+            }
+
+            // The value is left out, so the error does not require the enum to implement `Debug`.
+            impl #impl_g ::core::fmt::Debug for #error_name #type_g #where_clause {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.debug_struct(#debug)
+                        .field("expected", &self.expected)
+                        .field("actual", &self.actual)
+                        .finish_non_exhaustive()
+                }
             }
+
+            impl #impl_g ::std::error::Error for #error_name #type_g #where_clause {}
         }
     }
 
     #[allow(clippy::wrong_self_convention)]
-    pub(crate) fn to_tag_function(&mut self) {
-        let vs = self.input.vis();
-        let sp = Ident::new("to_tag", Span::call_site());
+    pub(crate) fn to_tag_function(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        let sp = self.config.function_ident("to_tag");
 
-        let tag_ident = Ident::new(
-            format!("{}Tag", self.input.0.ident).as_str(),
-            Span::call_site(),
-        );
+        let tag_ident = self.config.tag_enum_ident(self.input);
 
         let mut arms = vec![];
         for i in 0..self.input.variant_count() {
@@ -229,24 +833,15 @@ impl<'a> EnumFunctionsBuilder<'a> {
             }
         };
 
-        let mut ifn = None;
-        parse_function(ts, &mut ifn);
-
-        if let Some(ifn) = ifn {
-            self.functions.push(ifn);
-        } else {
-            panic!()
-        }
+        self.functions.push(parse_function(ts)?);
+        Ok(())
     }
 
-    pub(crate) fn as_ref_functions(&mut self) {
-        let vs = self.input.vis();
-        let sp = Ident::new("as_ref", Span::call_site());
+    pub(crate) fn as_ref_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        let sp = self.config.renamed_function_ident(fc, "as_ref");
 
-        let ref_ident = Ident::new(
-            format!("{}Ref", self.input.0.ident).as_str(),
-            Span::call_site(),
-        );
+        let ref_ident = self.config.ref_enum_ident(self.input);
 
         let mut arms = vec![];
         for i in 0..self.input.variant_count() {
@@ -263,7 +858,7 @@ impl<'a> EnumFunctionsBuilder<'a> {
             let variant_ident = &self.input.variant(i).ident;
             let body = match &self.input.variant(i).fields {
                 Fields::Unit => Expr::Verbatim(quote! {
-                    #ref_ident :: #variant_ident ,
+                    #ref_ident :: #variant_ident
                 }),
                 Fields::Unnamed(unnamed) => {
                     if unnamed.unnamed.len() == 1 {
@@ -343,42 +938,28 @@ impl<'a> EnumFunctionsBuilder<'a> {
             });
         }
 
-        let mut generics = self.input.generics().clone();
-        generics
-            .params
-            .push(syn::GenericParam::Lifetime(syn::LifetimeParam {
-                attrs: vec![],
-                lifetime: Lifetime::new("'_", Span::call_site()),
-                bounds: Punctuated::new(),
-                colon_token: None,
-            }));
+        let generics = self
+            .input
+            .view_generics(&Lifetime::new("'_", Span::call_site()));
+        let (_, type_g, _) = generics.split_for_impl();
 
         let ts = quote! {
-            #vs fn #sp (&self) -> #ref_ident #generics {
+            #vs fn #sp (&self) -> #ref_ident #type_g {
                 match self {
                     #(#arms)*
                 }
             }
         };
 
-        let mut ifn = None;
-        parse_function(ts, &mut ifn);
-
-        if let Some(ifn) = ifn {
-            self.functions.push(ifn);
-        } else {
-            panic!()
-        }
+        self.functions.push(parse_function(ts)?);
+        Ok(())
     }
 
-    pub(crate) fn as_mut_functions(&mut self) {
-        let vs = self.input.vis();
-        let sp = Ident::new("as_mut", Span::call_site());
+    pub(crate) fn as_mut_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        let sp = self.config.renamed_function_ident(fc, "as_mut");
 
-        let ref_ident = Ident::new(
-            format!("{}Mut", self.input.0.ident).as_str(),
-            Span::call_site(),
-        );
+        let ref_ident = self.config.mut_enum_ident(self.input);
 
         let mut arms = vec![];
         for i in 0..self.input.variant_count() {
@@ -395,7 +976,7 @@ impl<'a> EnumFunctionsBuilder<'a> {
             let variant_ident = &self.input.variant(i).ident;
             let body = match &self.input.variant(i).fields {
                 Fields::Unit => Expr::Verbatim(quote! {
-                    #ref_ident :: #variant_ident ,
+                    #ref_ident :: #variant_ident
                 }),
                 Fields::Unnamed(unnamed) => {
                     if unnamed.unnamed.len() == 1 {
@@ -475,39 +1056,27 @@ impl<'a> EnumFunctionsBuilder<'a> {
             });
         }
 
-        let mut generics = self.input.generics().clone();
-        generics
-            .params
-            .push(syn::GenericParam::Lifetime(syn::LifetimeParam {
-                attrs: vec![],
-                lifetime: Lifetime::new("'_", Span::call_site()),
-                bounds: Punctuated::new(),
-                colon_token: None,
-            }));
+        let generics = self
+            .input
+            .view_generics(&Lifetime::new("'_", Span::call_site()));
+        let (_, type_g, _) = generics.split_for_impl();
 
         let ts = quote! {
-            #vs fn #sp (&mut self) -> #ref_ident #generics {
+            #vs fn #sp (&mut self) -> #ref_ident #type_g {
                 match self {
                     #(#arms)*
                 }
             }
         };
 
-        let mut ifn = None;
-        parse_function(ts, &mut ifn);
-
-        if let Some(ifn) = ifn {
-            self.functions.push(ifn);
-        } else {
-            panic!()
-        }
+        self.functions.push(parse_function(ts)?);
+        Ok(())
     }
 
-    pub(crate) fn get_functions(&mut self) {
-        let vs = self.input.vis();
-        for i in 0..self.input.variant_count() {
-            let nm = self.input.variant_snake_case_name(i);
-            let sp = Ident::new(format!("get_{nm}").as_str(), Span::call_site());
+    pub(crate) fn get_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self.input.helper_variants("get") {
+            let sp = self.config.method_ident(self.input, "get_{}", i);
             let syn::Arm { pat, body, .. } = self.input.match_variant_to_tuple(i, None);
 
             let return_type = self.input.variant_type(i);
@@ -520,54 +1089,18 @@ impl<'a> EnumFunctionsBuilder<'a> {
                 }
             };
 
-            let mut ufn = None;
-            parse_function(ts, &mut ufn);
-
-            if let Some(ufn) = ufn {
-                self.functions.push(ufn);
-            } else {
-                panic!()
-            }
+            self.functions.push(parse_function(ts)?);
         }
+        Ok(())
     }
 
-    pub(crate) fn get_ref_functions(&mut self) {
-        let vs = self.input.vis();
-        for i in 0..self.input.variant_count() {
-            let nm = self.input.variant_snake_case_name(i);
-            let sp = Ident::new(format!("get_ref_{nm}").as_str(), Span::call_site());
+    pub(crate) fn get_ref_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self.input.helper_variants("get_ref") {
+            let sp = self.config.method_ident(self.input, "get_ref_{}", i);
             let syn::Arm { pat, body, .. } = self.input.match_variant_to_tuple(i, None);
 
-            let return_type = self.input.variant_type(i);
-
-            let rt = match return_type {
-                ty @ Type::Path(_) => Type::Reference(syn::TypeReference {
-                    and_token: token::And {
-                        spans: [Span::call_site(); 1],
-                    },
-                    lifetime: None,
-                    mutability: None,
-                    elem: Box::new(ty),
-                }),
-                Type::Tuple(tuple) => Type::Tuple(syn::TypeTuple {
-                    paren_token: tuple.paren_token,
-                    elems: tuple
-                        .elems
-                        .into_iter()
-                        .map(|t| {
-                            Type::Reference(syn::TypeReference {
-                                and_token: token::And {
-                                    spans: [Span::call_site(); 1],
-                                },
-                                lifetime: None,
-                                mutability: None,
-                                elem: Box::new(t),
-                            })
-                        })
-                        .collect(),
-                }),
-                _ => panic!(),
-            };
+            let rt = self.input.variant_ref_type(i, false, None);
 
             let ts = quote! {
                 #vs fn #sp (&self) -> Option<#rt> {
@@ -578,93 +1111,257 @@ impl<'a> EnumFunctionsBuilder<'a> {
                 }
             };
 
-            let mut ufn = None;
-            parse_function(ts, &mut ufn);
-
-            if let Some(ufn) = ufn {
-                self.functions.push(ufn);
-            } else {
-                panic!()
-            }
+            self.functions.push(parse_function(ts)?);
         }
+        Ok(())
     }
 
-    pub(crate) fn get_mut_functions(&mut self) {
-        let vs = self.input.vis();
-        for i in 0..self.input.variant_count() {
-            let nm = self.input.variant_snake_case_name(i);
-            let sp = Ident::new(format!("get_mut_{nm}").as_str(), Span::call_site());
+    pub(crate) fn get_mut_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self.input.helper_variants("get_mut") {
+            let sp = self.config.method_ident(self.input, "get_mut_{}", i);
             let syn::Arm { pat, body, .. } = self.input.match_variant_to_tuple(i, None);
 
-            let return_type = self.input.variant_type(i);
-            let mutability = Some(token::Mut {
-                span: Span::call_site(),
-            });
-
-            let rt = match return_type {
-                ty @ Type::Path(_) => Type::Reference(syn::TypeReference {
-                    and_token: token::And {
-                        spans: [Span::call_site(); 1],
-                    },
-                    lifetime: None,
-                    mutability,
-                    elem: Box::new(ty),
-                }),
-                Type::Tuple(tuple) => Type::Tuple(syn::TypeTuple {
-                    paren_token: tuple.paren_token,
-                    elems: tuple
-                        .elems
-                        .into_iter()
-                        .map(|t| {
-                            Type::Reference(syn::TypeReference {
-                                and_token: token::And {
-                                    spans: [Span::call_site(); 1],
-                                },
-                                lifetime: None,
-                                mutability,
-                                elem: Box::new(t),
-                            })
-                        })
-                        .collect(),
-                }),
-                _ => panic!(),
-            };
+            let rt = self.input.variant_ref_type(i, true, None);
 
             let ts = quote! {
                 #vs fn #sp (&mut self) -> Option<#rt> {
                     match self {
                         #pat => { Some ( #body ) },
-                        _ => panic!()
+                        _ => None
+                    }
+                }
+            };
+
+            self.functions.push(parse_function(ts)?);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn owned_into_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self.input.helper_variants("into") {
+            let sp = self.config.method_ident(self.input, "into_{}", i);
+            let syn::Arm { pat, body, .. } = self.input.match_variant_to_tuple(i, None);
+
+            let return_type = self.input.variant_type(i);
+            let ts = quote! {
+                #vs fn #sp (self) -> Result<#return_type, Self> {
+                    match self {
+                        #pat => { Ok ( #body ) },
+                        other => Err(other)
                     }
                 }
             };
 
-            let mut ufn = None;
-            parse_function(ts, &mut ufn);
+            self.functions.push(parse_function(ts)?);
+        }
+        Ok(())
+    }
+
+    /// Implements `From<T>` for the enum and `TryFrom<Enum>` for `T`, where `T` is the single
+    /// unnamed field of a variant. Only types which identify their variant are supported.
+    pub(crate) fn payload_from_impls(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        if let Some(vis) = &fc.vis {
+            return Err(syn::Error::new_spanned(
+                vis,
+                "`from` generates trait impls, which have no visibility",
+            ));
+        }
 
-            if let Some(ufn) = ufn {
-                self.functions.push(ufn);
+        let enum_name = &self.input.item.ident;
+        let (impl_g, type_g, where_clause) = self.input.generics().split_for_impl();
+        let candidates: Vec<usize> = self
+            .input
+            .helper_variants("from")
+            .filter(|i| {
+                matches!(&self.input.variant(*i).fields, Fields::Unnamed(f) if f.unnamed.len() == 1)
+            })
+            .collect();
+
+        let mut diagnostics = Diagnostics::default();
+        for &i in &candidates {
+            let variant_name = &self.input.variant(i).ident;
+            let ty = self.input.variant_type(i);
+            let same: Vec<usize> = candidates
+                .iter()
+                .copied()
+                .filter(|&j| j != i && same_type(&ty, &self.input.variant_type(j)))
+                .collect();
+            let hint =
+                "Add `#[enum_helper(skip(from))]` to the variant or use `from(skip_ambiguous)`";
+            let error = if self.input.is_type_parameter(&ty) {
+                Some(syn::Error::new_spanned(
+                    &ty,
+                    format!(
+                        "the payload of `{variant_name}` is a type parameter, `from` can not be implemented for it. {hint}"
+                    ),
+                ))
+            } else if same.first().is_some_and(|&j| j < i) {
+                // Reported together with the first variant of this type.
+                continue;
+            } else if !same.is_empty() {
+                let others = same
+                    .iter()
+                    .map(|&j| format!("`{}`", self.input.variant(j).ident))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut error = syn::Error::new_spanned(
+                    &ty,
+                    format!(
+                        "`{variant_name}` has the same payload type as {others}, `from` can not tell them apart. {hint}"
+                    ),
+                );
+                for &j in &same {
+                    error.combine(syn::Error::new_spanned(
+                        self.input.variant_type(j),
+                        format!(
+                            "`{}` has the same payload type",
+                            self.input.variant(j).ident
+                        ),
+                    ));
+                }
+                Some(error)
             } else {
-                panic!()
+                None
+            };
+            if let Some(error) = error {
+                if !fc.skip_ambiguous {
+                    diagnostics.push(error);
+                }
+                continue;
             }
+
+            // rustc reports conflicting impls at the payload type.
+            let span = Span::call_site().located_at(ty.span());
+            let from = quote! {
+                impl #impl_g From<#ty> for #enum_name #type_g #where_clause {
+                    fn from(value: #ty) -> Self {
+                        Self :: #variant_name (value)
+                    }
+                }
+            };
+            let try_from = quote! {
+                impl #impl_g TryFrom<#enum_name #type_g> for #ty #where_clause {
+                    type Error = #enum_name #type_g;
+
+                    fn try_from(value: #enum_name #type_g) -> Result<Self, #enum_name #type_g> {
+                        match value {
+                            #enum_name :: #variant_name (value) => Ok(value),
+                            other => Err(other),
+                        }
+                    }
+                }
+            };
+            self.impls.push(syn::parse2(respan(from, span))?);
+            self.impls.push(syn::parse2(respan(try_from, span))?);
+        }
+        diagnostics.finish()
+    }
+
+    /// Implements the traits of `enum-utility-traits` for the generated enums: `Tagged` for the
+    /// TagEnum, `HasRefView` for the RefEnum and `HasMutView` for the MutEnum.
+    fn trait_impls(&mut self) -> syn::Result<()> {
+        let path = self.config.traits_path();
+        let enum_name = &self.input.item.ident;
+        let (impl_g, type_g, where_clause) = self.input.generics().split_for_impl();
+
+        if self.config.tag_enum.is_some() {
+            let tag_ident = self.config.tag_enum_ident(self.input);
+            let tag_match = self.input.variant_match(None, |i| {
+                let variant_ident = &self.input.variant(i).ident;
+                quote! { #tag_ident :: #variant_ident }
+            });
+            self.impls.push(syn::parse2(quote! {
+                impl #impl_g #path::Tagged for #enum_name #type_g #where_clause {
+                    type Tag = #tag_ident;
+
+                    fn tag(&self) -> #tag_ident {
+                        #tag_match
+                    }
+                }
+            })?);
+        }
+
+        let view_lifetime = Lifetime::new("'view", Span::call_site());
+        let view_generics = self.input.view_generics(&view_lifetime);
+        let (_, view_type_g, _) = view_generics.split_for_impl();
+
+        if self.config.ref_enum.is_some() {
+            let ref_ident = self.config.ref_enum_ident(self.input);
+            let conversion = self
+                .input
+                .conversion_match(quote! { self }, None, &ref_ident);
+            self.impls.push(syn::parse2(quote! {
+                impl #impl_g #path::HasRefView for #enum_name #type_g #where_clause {
+                    type Ref<#view_lifetime> = #ref_ident #view_type_g
+                    where
+                        Self: #view_lifetime;
+
+                    fn view(&self) -> <Self as #path::HasRefView>::Ref<'_> {
+                        #conversion
+                    }
+                }
+            })?);
+        }
+
+        if self.config.mut_enum.is_some() {
+            let mut_ident = self.config.mut_enum_ident(self.input);
+            let conversion = self
+                .input
+                .conversion_match(quote! { self }, None, &mut_ident);
+            self.impls.push(syn::parse2(quote! {
+                impl #impl_g #path::HasMutView for #enum_name #type_g #where_clause {
+                    type Mut<#view_lifetime> = #mut_ident #view_type_g
+                    where
+                        Self: #view_lifetime;
+
+                    fn view_mut(&mut self) -> <Self as #path::HasMutView>::Mut<'_> {
+                        #conversion
+                    }
+                }
+            })?);
         }
+        Ok(())
+    }
+
+    pub(crate) fn metadata_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let (functions, constants) =
+            metadata_items(self.input, self.config, fc, &self.input.item.ident)?;
+        self.functions.extend(functions);
+        self.constants.extend(constants);
+        Ok(())
+    }
+
+    /// Names of all generated functions.
+    pub(crate) fn function_idents(&self) -> impl Iterator<Item = &Ident> {
+        self.functions.iter().map(|f| &f.sig.ident)
     }
 
     pub(crate) fn token_stream(&self) -> TokenStream {
         let _visibility = &self.input.vis();
-        let enum_name = &self.input.0.ident;
+        let enum_name = &self.input.item.ident;
         let functions = &self.functions;
         let (impl_g, type_g, where_clause) = self.input.generics().split_for_impl();
 
-        if !functions.is_empty() {
+        let impls = &self.impls;
+        let types = &self.types;
+        if !functions.is_empty() || !self.constants.is_empty() {
+            let allow = self.config.non_snake_case_attribute();
+            let constants = &self.constants;
             let functions = quote! {
+                #allow
                 impl #impl_g #enum_name #type_g #where_clause {
+                    #(#constants)*
                     #(#functions)*
                 }
+
+                #(#types)*
+                #(#impls)*
             };
             TokenStream::from(functions)
         } else {
-            TokenStream::new()
+            TokenStream::from(quote! { #(#types)* #(#impls)* })
         }
     }
 }
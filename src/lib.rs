@@ -1,208 +1,353 @@
 #![doc = include_str!("../README.md")]
 
+use config::{Config, FunctionConfig, VariantConfig, HELPER_ATTRIBUTE};
+use diagnostics::Diagnostics;
 use functions_builder::EnumFunctionsBuilder;
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::{quote, ToTokens};
 use ref_enum_builder::RefEnumBuilder;
 use syn::{
+    ext::IdentExt,
     parse::Parser,
     parse_macro_input,
     punctuated::Punctuated,
     token::{self},
-    Expr, Fields, ItemEnum, ItemFn, Token, Type, TypeTuple, Variant, Visibility,
+    Data, DeriveInput, Expr, Fields, ImplItemConst, ItemEnum, ItemFn, Lifetime, Token, Type,
+    TypeReference, TypeTuple, Variant, Visibility,
 };
 use tag_enum_builder::TagEnumBuilder;
 
+/// Name of the derive macro. It must not be applied to the generated enums.
+const DERIVE_NAME: &str = "EnumHelper";
+
+/// Attributes which the derive macro copies to the generated enums. It does not see the other
+/// derives of the enum, so their helper attributes, like `#[default]`, are left out.
+const DERIVE_COPIED_ATTRIBUTES: &[&str] = &["doc", "cfg", "allow"];
+
+pub(crate) mod case;
+pub(crate) mod config;
+pub(crate) mod diagnostics;
 pub(crate) mod functions_builder;
 pub(crate) mod ref_enum_builder;
 pub(crate) mod tag_enum_builder;
 
 #[proc_macro_attribute]
 pub fn generate_enum_helper(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let mut enum_stream = item.clone();
-
-    let parser = Punctuated::<Ident, Token![,]>::parse_separated_nonempty;
-    let attributes = parser.parse(attr).unwrap();
     let input = parse_macro_input!(item as ItemEnum);
+    expand(Config::parse(attr.into()), input, true)
+}
 
-    let mut generate_tag_enum = false;
-    let mut generate_ref_enum = false;
-    let mut generate_mut_enum = false;
-
-    let mut create_is_functions = false;
-    let mut create_unwrap_functions = false;
-    let mut create_unwrap_ref_functions = false;
-    let mut create_unwrap_ref_mut_functions = false;
-    let mut create_to_tag_functions = false;
-    let mut create_as_ref_functions = false;
-    let mut create_as_mut_functions = false;
-    let mut create_get_functions = false;
-    let mut create_get_ref_functions = false;
-    let mut create_get_mut_functions = false;
-    for item in attributes {
-        match item.to_string().as_str() {
-            "TagEnum" => generate_tag_enum = true,
-            "RefEnum" => generate_ref_enum = true,
-            "MutEnum" => generate_mut_enum = true,
-            "is" => create_is_functions = true,
-            "unwrap" => create_unwrap_functions = true,
-            "unwrap_ref" => create_unwrap_ref_functions = true,
-            "unwrap_mut" => create_unwrap_ref_mut_functions = true,
-            "to_tag" => create_to_tag_functions = true,
-            "as_ref" => create_as_ref_functions = true,
-            "as_mut" => create_as_mut_functions = true,
-            "get" => create_get_functions = true,
-            "get_ref" => create_get_ref_functions = true,
-            "get_mut" => create_get_mut_functions = true,
-            _ => panic!(),
-        }
+#[proc_macro_derive(EnumHelper, attributes(enum_helper))]
+pub fn derive_enum_helper(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let config = Config::parse_attributes(&input.attrs);
+    match derive_input_to_enum(input) {
+        Ok(input) => expand(config, input, false),
+        Err(error) => TokenStream::from(error.to_compile_error()),
     }
+}
 
-    let input_enum = InputEnum(input);
-    if create_is_functions
-        || create_unwrap_functions
-        || create_unwrap_ref_functions
-        || create_unwrap_ref_mut_functions
-        || create_to_tag_functions
-        || create_as_ref_functions
-        || create_as_mut_functions
-        || create_get_functions
-        || create_get_ref_functions
-        || create_get_mut_functions
-    {
-        let mut functions_builder = EnumFunctionsBuilder::new(&input_enum);
-        if create_is_functions {
-            functions_builder.is_functions();
-        }
-        if create_unwrap_functions {
-            functions_builder.unwrap_functions();
-        }
-        if create_unwrap_ref_functions {
-            functions_builder.unwrap_ref_functions();
-        }
-        if create_unwrap_ref_mut_functions {
-            functions_builder.unwrap_mut_functions();
-        }
-        if create_to_tag_functions {
-            functions_builder.to_tag_function();
-        }
-        if create_as_ref_functions {
-            functions_builder.as_ref_functions();
-        }
-        if create_as_mut_functions {
-            functions_builder.as_mut_functions();
-        }
-        if create_get_functions {
-            functions_builder.get_functions();
-        }
-        if create_get_ref_functions {
-            functions_builder.get_ref_functions();
-        }
-        if create_get_mut_functions {
-            functions_builder.get_mut_functions();
-        }
+/// Shared implementation of both macros. The attribute macro re-emits the enum, the derive
+/// macro does not.
+fn expand(config: syn::Result<Config>, input: ItemEnum, emit_enum: bool) -> TokenStream {
+    let mut diagnostics = Diagnostics::default();
+    let mut config = diagnostics.check(config);
+    let input_enum = InputEnum::new(input, emit_enum, &mut diagnostics);
+    if let Some(config) = config.as_mut() {
+        diagnostics.check(config.restrict_to(&input_enum));
+    }
 
-        let ts = functions_builder.token_stream();
-        enum_stream.extend([ts]);
+    let mut stream = TokenStream::new();
+    if emit_enum {
+        // The enum is emitted without the variant attributes consumed by this macro.
+        stream.extend([TokenStream::from(input_enum.item.to_token_stream())]);
+    }
+    if let Some(config) = config.filter(|_| !diagnostics.has_errors()) {
+        stream.extend(diagnostics.check(generate_helpers(&input_enum, &config)));
     }
+    if let Err(error) = diagnostics.finish() {
+        stream.extend([TokenStream::from(error.to_compile_error())]);
+    }
+    stream
+}
 
-    if generate_tag_enum {
-        let mut tag_enum_builder = TagEnumBuilder::new(&input_enum);
-        if create_is_functions {
-            tag_enum_builder.is_functions();
-        }
-        let ts = tag_enum_builder.token_stream();
-        enum_stream.extend([ts]);
+fn derive_input_to_enum(input: DeriveInput) -> syn::Result<ItemEnum> {
+    let Data::Enum(data) = input.data else {
+        return Err(syn::Error::new_spanned(
+            input.ident,
+            "EnumHelper can only be derived for enums",
+        ));
+    };
+
+    Ok(ItemEnum {
+        attrs: input.attrs,
+        vis: input.vis,
+        enum_token: data.enum_token,
+        ident: input.ident,
+        generics: input.generics,
+        brace_token: data.brace_token,
+        variants: data.variants,
+    })
+}
+
+/// Runs all builders requested by `config`. Errors of all builders are collected.
+fn generate_helpers(input_enum: &InputEnum, config: &Config) -> syn::Result<TokenStream> {
+    let mut diagnostics = Diagnostics::default();
+    let mut stream = TokenStream::new();
+
+    let mut type_idents = vec![input_enum.item.ident.clone()];
+    type_idents.extend(
+        config
+            .tag_enum
+            .as_ref()
+            .map(|_| config.tag_enum_ident(input_enum)),
+    );
+    type_idents.extend(
+        config
+            .ref_enum
+            .as_ref()
+            .map(|_| config.ref_enum_ident(input_enum)),
+    );
+    type_idents.extend(
+        config
+            .mut_enum
+            .as_ref()
+            .map(|_| config.mut_enum_ident(input_enum)),
+    );
+    if config.try_unwrap.is_some() {
+        type_idents.push(config.wrong_variant_ident(input_enum));
+    }
+    if config
+        .tag_enum
+        .as_ref()
+        .is_some_and(|c| c.strings.is_some())
+    {
+        type_idents.push(config.parse_tag_error_ident(input_enum));
+    }
+    check_duplicate_idents(&type_idents, &mut diagnostics);
+
+    let mut functions_builder = EnumFunctionsBuilder::new(input_enum, config);
+    diagnostics.check(functions_builder.generate());
+    check_duplicate_idents(functions_builder.function_idents(), &mut diagnostics);
+    stream.extend([functions_builder.token_stream()]);
+
+    if let Some(tag_config) = &config.tag_enum {
+        let mut tag_enum_builder = TagEnumBuilder::new(input_enum, config, tag_config);
+        diagnostics.check(tag_enum_builder.generate());
+        check_duplicate_idents(tag_enum_builder.function_idents(), &mut diagnostics);
+        stream.extend([tag_enum_builder.token_stream()]);
     }
 
-    if generate_ref_enum {
-        let mut ref_enum_builder = RefEnumBuilder::new(&input_enum, false);
-        if create_is_functions {
-            ref_enum_builder.is_functions();
-        }
-        if create_unwrap_functions {
-            ref_enum_builder.unwrap_functions();
-        }
-        if create_to_tag_functions {
-            ref_enum_builder.to_tag_functions();
-        }
-        if create_get_functions {
-            ref_enum_builder.get_functions();
-        }
-        let ts = ref_enum_builder.token_stream();
-        enum_stream.extend([ts]);
+    if let Some(ref_config) = &config.ref_enum {
+        let mut ref_enum_builder = RefEnumBuilder::new(input_enum, config, ref_config, false);
+        diagnostics.check(ref_enum_builder.generate());
+        check_duplicate_idents(ref_enum_builder.function_idents(), &mut diagnostics);
+        stream.extend(diagnostics.check(ref_enum_builder.token_stream()));
     }
 
-    if generate_mut_enum {
-        let mut ref_enum_builder = RefEnumBuilder::new(&input_enum, true);
-        if create_is_functions {
-            ref_enum_builder.is_functions();
-        }
-        if create_unwrap_functions {
-            ref_enum_builder.unwrap_functions();
-        }
-        if create_to_tag_functions {
-            ref_enum_builder.to_tag_functions();
-        }
-        if create_get_functions {
-            ref_enum_builder.get_functions();
+    if let Some(mut_config) = &config.mut_enum {
+        let mut ref_enum_builder = RefEnumBuilder::new(input_enum, config, mut_config, true);
+        diagnostics.check(ref_enum_builder.generate());
+        check_duplicate_idents(ref_enum_builder.function_idents(), &mut diagnostics);
+        stream.extend(diagnostics.check(ref_enum_builder.token_stream()));
+    }
+
+    diagnostics.finish().map(|()| stream)
+}
+
+/// Reports every name in `idents` which is generated more than once. Generated names carry the
+/// span of the variant or option they are derived from, so the errors point at both sources.
+fn check_duplicate_idents<'i>(
+    idents: impl IntoIterator<Item = &'i Ident>,
+    diagnostics: &mut Diagnostics,
+) {
+    let mut seen: Vec<&Ident> = vec![];
+    for ident in idents {
+        let name = ident.unraw();
+        match seen.iter().find(|s| s.unraw() == name) {
+            Some(first) => {
+                let mut error = syn::Error::new(
+                    first.span(),
+                    format!("`{name}` is generated more than once"),
+                );
+                error.combine(syn::Error::new(
+                    ident.span(),
+                    format!("`{name}` is also generated here"),
+                ));
+                diagnostics.push(error);
+            }
+            None => seen.push(ident),
         }
-        let ts = ref_enum_builder.token_stream();
-        enum_stream.extend([ts]);
     }
+}
 
-    enum_stream
+/// A named field which several variants have in common.
+struct SharedField<'a> {
+    ident: &'a Ident,
+    ty: &'a Type,
+    /// Indices of the variants having the field.
+    variants: Vec<usize>,
 }
 
-pub(crate) struct InputEnum(ItemEnum);
+pub(crate) struct InputEnum {
+    item: ItemEnum,
+    variant_configs: Vec<VariantConfig>,
+}
 
 impl InputEnum {
+    /// Reads the `#[enum_helper(...)]` attributes of all variants and removes them from `item`.
+    /// Unless the enum is re-emitted, only the attributes which are safe to copy to the generated
+    /// enums are kept.
+    fn new(mut item: ItemEnum, emit_enum: bool, diagnostics: &mut Diagnostics) -> Self {
+        let copied = |attr: &syn::Attribute| {
+            emit_enum
+                || DERIVE_COPIED_ATTRIBUTES
+                    .iter()
+                    .any(|name| attr.path().is_ident(name))
+        };
+        let mut variant_configs = vec![];
+        item.attrs.retain(copied);
+        for variant in item.variants.iter_mut() {
+            let config = diagnostics.check(VariantConfig::parse(&variant.attrs));
+            variant_configs.push(config.unwrap_or_default());
+            variant
+                .attrs
+                .retain(|attr| !attr.path().is_ident(HELPER_ATTRIBUTE) && copied(attr));
+            for field in variant.fields.iter_mut() {
+                field.attrs.retain(copied);
+            }
+        }
+
+        Self {
+            item,
+            variant_configs,
+        }
+    }
+
     fn vis(&self) -> &Visibility {
-        &self.0.vis
+        &self.item.vis
     }
 
     fn name(&self) -> String {
-        format!("{}", self.0.ident)
-    }
-
-    fn variant_snake_case_name(&self, i: usize) -> String {
-        let variant_name = self.0.variants[i].ident.to_string();
-        let mut snake_case_name = String::new();
-        for c in variant_name.chars() {
-            if c.is_uppercase() && snake_case_name.is_empty() {
-                snake_case_name += format!("{}", c.to_ascii_lowercase()).as_str();
-            } else if c.is_uppercase() {
-                snake_case_name += format!("_{}", c.to_ascii_lowercase()).as_str();
-            } else {
-                snake_case_name += format!("{c}").as_str();
-            }
+        format!("{}", self.item.ident)
+    }
+
+    /// Lowercase words of the variant name, used to build the names of generated functions.
+    fn variant_words(&self, i: usize) -> Vec<String> {
+        match &self.variant_configs[i].rename {
+            Some(rename) => case::words(rename),
+            None => case::words(&self.item.variants[i].ident.unraw().to_string()),
         }
-        snake_case_name
     }
 
     fn generics(&self) -> &syn::Generics {
-        &self.0.generics
+        &self.item.generics
+    }
+
+    /// Generics of the RefEnum or the MutEnum: the generics of the enum and `lifetime`.
+    fn view_generics(&self, lifetime: &Lifetime) -> syn::Generics {
+        let mut generics = self.item.generics.clone();
+        generics
+            .params
+            .push(syn::GenericParam::Lifetime(syn::LifetimeParam {
+                attrs: vec![],
+                bounds: Punctuated::new(),
+                colon_token: None,
+                lifetime: lifetime.clone(),
+            }));
+        generics
     }
 
     fn attributes(&self) -> &Vec<syn::Attribute> {
-        &self.0.attrs
+        &self.item.attrs
     }
 
     fn iter_variants(&self) -> impl Iterator<Item = &Variant> {
-        self.0.variants.iter()
+        self.item.variants.iter()
+    }
+
+    /// Named fields which appear with the same type in all variants or in at least two variants.
+    /// Fields whose type differs between variants are left out.
+    fn shared_fields(&self) -> Vec<SharedField<'_>> {
+        let mut shared: Vec<SharedField> = vec![];
+        let mut conflicting = vec![];
+        for (i, variant) in self.iter_variants().enumerate() {
+            let Fields::Named(fields) = &variant.fields else {
+                continue;
+            };
+            for field in &fields.named {
+                let Some(ident) = &field.ident else {
+                    continue;
+                };
+                match shared.iter_mut().find(|s| s.ident == ident) {
+                    Some(s) if same_type(s.ty, &field.ty) => s.variants.push(i),
+                    Some(_) => conflicting.push(ident),
+                    None => shared.push(SharedField {
+                        ident,
+                        ty: &field.ty,
+                        variants: vec![i],
+                    }),
+                }
+            }
+        }
+        shared.retain(|s| {
+            let shared_enough = s.variants.len() >= 2 || s.variants.len() == self.variant_count();
+            shared_enough && !conflicting.contains(&s.ident)
+        });
+        shared
+    }
+
+    /// Indices of all variants for which the per-variant `helper` is generated.
+    fn helper_variants<'s>(&'s self, helper: &'s str) -> impl Iterator<Item = usize> + 's {
+        (0..self.variant_count()).filter(move |i| !self.variant_configs[*i].skips(helper))
     }
 
     fn variant_count(&self) -> usize {
-        self.0.variants.len()
+        self.item.variants.len()
     }
 
     fn variant(&self, i: usize) -> &Variant {
-        &self.0.variants[i]
+        &self.item.variants[i]
+    }
+
+    /// Whether `ty` is a type parameter of the enum, possibly behind a reference or a `Box`. Such
+    /// a type may be the payload of any other variant and trait impls for it break the orphan
+    /// rule.
+    fn is_type_parameter(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Reference(reference) => self.is_type_parameter(&reference.elem),
+            Type::Paren(paren) => self.is_type_parameter(&paren.elem),
+            Type::Group(group) => self.is_type_parameter(&group.elem),
+            Type::Path(path) if path.qself.is_none() => {
+                let Some(last) = path.path.segments.last() else {
+                    return false;
+                };
+                match &last.arguments {
+                    syn::PathArguments::None if path.path.segments.len() == 1 => self
+                        .generics()
+                        .type_params()
+                        .any(|param| param.ident == last.ident),
+                    syn::PathArguments::AngleBracketed(arguments) if last.ident == "Box" => {
+                        matches!(
+                            arguments.args.first(),
+                            Some(syn::GenericArgument::Type(inner)) if self.is_type_parameter(inner)
+                        )
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether variant `i` has a payload. Helpers transforming the payload skip unit variants.
+    fn has_fields(&self, i: usize) -> bool {
+        !self.item.variants[i].fields.is_empty()
     }
 
     fn variant_type(&self, i: usize) -> Type {
-        let elems: Punctuated<_, _> = self.0.variants[i]
+        let elems: Punctuated<_, _> = self.item.variants[i]
             .fields
             .iter()
             .map(|f| f.ty.clone())
@@ -224,9 +369,44 @@ impl InputEnum {
         })
     }
 
+    /// Type of the fields of variant `i` when borrowed: `&T` for a single field and a tuple of
+    /// references otherwise. The references are elided unless `lifetime` is given.
+    fn variant_ref_type(&self, i: usize, mutable: bool, lifetime: Option<&Lifetime>) -> Type {
+        let reference = |ty: &Type| {
+            Type::Reference(TypeReference {
+                and_token: token::And {
+                    spans: [Span::call_site(); 1],
+                },
+                lifetime: lifetime.cloned(),
+                mutability: mutable.then(|| token::Mut {
+                    span: Span::call_site(),
+                }),
+                elem: Box::new(ty.clone()),
+            })
+        };
+
+        let fields = &self.item.variants[i].fields;
+        if fields.len() == 1 {
+            if let Some(field) = fields.iter().next() {
+                return reference(&field.ty);
+            }
+        }
+
+        let group = proc_macro2::Group::new(
+            proc_macro2::Delimiter::Parenthesis,
+            proc_macro2::TokenStream::new(),
+        );
+        Type::Tuple(TypeTuple {
+            paren_token: token::Paren {
+                span: group.delim_span(),
+            },
+            elems: fields.iter().map(|f| reference(&f.ty)).collect(),
+        })
+    }
+
     fn match_variant(&self, i: usize, enum_ident: Option<Ident>) -> syn::Pat {
         let variant = self.variant(i);
-        let enum_name = enum_ident.as_ref().unwrap_or(&self.0.ident);
+        let enum_name = enum_ident.as_ref().unwrap_or(&self.item.ident);
         let variant_name = &self.variant(i).ident;
         let pattern = match &variant.fields {
             Fields::Unit => {
@@ -259,6 +439,109 @@ impl InputEnum {
         syn::Pat::Verbatim(pattern)
     }
 
+    /// Expression constructing variant `i` from `value`, which has the shape returned by
+    /// `match_variant_to_tuple`: the single field or a tuple of all fields.
+    fn construct_variant(
+        &self,
+        i: usize,
+        enum_ident: Option<Ident>,
+        value: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let enum_name = enum_ident.as_ref().unwrap_or(&self.item.ident);
+        let variant_name = &self.variant(i).ident;
+        let fields = &self.variant(i).fields;
+        let bindings: Vec<Ident> = fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| match &field.ident {
+                Some(ident) => ident.clone(),
+                None => Ident::new(&format!("e{index}"), Span::call_site()),
+            })
+            .collect();
+
+        match (fields, bindings.as_slice()) {
+            (Fields::Unit, _) => quote! { { let () = #value; #enum_name :: #variant_name } },
+            (Fields::Unnamed(_), [_]) => quote! { #enum_name :: #variant_name ( #value ) },
+            (Fields::Named(_), [binding]) => {
+                quote! { #enum_name :: #variant_name { #binding: #value } }
+            }
+            (Fields::Unnamed(_), _) => quote! {
+                {
+                    let ( #(#bindings ,)* ) = #value;
+                    #enum_name :: #variant_name ( #(#bindings ,)* )
+                }
+            },
+            (Fields::Named(_), _) => quote! {
+                {
+                    let ( #(#bindings ,)* ) = #value;
+                    #enum_name :: #variant_name { #(#bindings ,)* }
+                }
+            },
+        }
+    }
+
+    /// `match value` expression converting each variant of `source` to the variant of the same
+    /// name of `target`, e.g. from a reference to the enum to the RefEnum.
+    fn conversion_match(
+        &self,
+        value: proc_macro2::TokenStream,
+        source: Option<Ident>,
+        target: &Ident,
+    ) -> proc_macro2::TokenStream {
+        let arms = (0..self.variant_count()).map(|i| {
+            let syn::Arm { pat, body, .. } = self.match_variant_to_tuple(i, source.clone());
+            let value = self.construct_variant(i, Some(target.clone()), body.to_token_stream());
+            quote! { #pat => #value }
+        });
+        quote! {
+            match #value {
+                #(#arms ,)*
+            }
+        }
+    }
+
+    /// `match self` expression evaluating to `value(i)` for variant `i`. The patterns do not
+    /// depend on the fields, so the match works for the TagEnum as well.
+    fn variant_match(
+        &self,
+        enum_ident: Option<Ident>,
+        value: impl Fn(usize) -> proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let enum_name = enum_ident.as_ref().unwrap_or(&self.item.ident);
+        let arms = (0..self.variant_count()).map(|i| {
+            let variant_name = &self.variant(i).ident;
+            let value = value(i);
+            quote! { #enum_name :: #variant_name { .. } => #value }
+        });
+        quote! {
+            match self {
+                #(#arms ,)*
+            }
+        }
+    }
+
+    /// Panic of `function` called on the wrong variant, e.g. `called unwrap_int32 on
+    /// MyEnum::Float`. Functions with a `msg` argument use it instead, like `Result::expect`.
+    fn wrong_variant_panic(
+        &self,
+        function: &Ident,
+        enum_ident: Option<Ident>,
+        expect: bool,
+    ) -> proc_macro2::TokenStream {
+        let enum_name = enum_ident.as_ref().unwrap_or(&self.item.ident).unraw();
+        let found = self.variant_match(enum_ident, |i| {
+            let name = self.variant(i).ident.unraw().to_string();
+            quote! { #name }
+        });
+        if expect {
+            let format = format!("{{}}: found {enum_name}::{{}}");
+            quote! { panic!(#format, msg, #found) }
+        } else {
+            let format = format!("called {} on {enum_name}::{{}}", function.unraw());
+            quote! { panic!(#format, #found) }
+        }
+    }
+
     fn match_variant_to_tuple(&self, i: usize, enum_ident: Option<Ident>) -> syn::Arm {
         let (pat, body) = match &self.variant(i).fields {
             Fields::Unit => {
@@ -268,7 +551,7 @@ impl InputEnum {
                 );
 
                 (
-                    self.match_variant(i, None),
+                    self.match_variant(i, enum_ident),
                     Box::new(Expr::Tuple(syn::ExprTuple {
                         attrs: vec![],
                         paren_token: token::Paren {
@@ -309,7 +592,7 @@ impl InputEnum {
                 let pattern_path = {
                     let mut punctuated = Punctuated::new();
                     punctuated.push(syn::PathSegment {
-                        ident: enum_ident.unwrap_or(self.0.ident.clone()),
+                        ident: enum_ident.unwrap_or(self.item.ident.clone()),
                         arguments: syn::PathArguments::None,
                     });
                     punctuated.push(syn::PathSegment {
@@ -337,24 +620,7 @@ impl InputEnum {
                     elems: patterns,
                 });
 
-                let body = if elements.len() == 1 {
-                    let syn::Expr::Path(syn::ExprPath { path, ..}) = elements.first().unwrap() else {
-                        panic!()
-                    };
-                    Box::new(Expr::Path(syn::ExprPath {
-                        attrs: vec![],
-                        qself: None,
-                        path: path.clone(),
-                    }))
-                } else {
-                    Box::new(Expr::Tuple(syn::ExprTuple {
-                        attrs: vec![],
-                        paren_token: token::Paren {
-                            span: group.delim_span(),
-                        },
-                        elems: elements,
-                    }))
-                };
+                let body = Box::new(single_or_tuple(elements));
 
                 (pat, body)
             }
@@ -393,7 +659,7 @@ impl InputEnum {
                 let pattern_path = {
                     let mut punctuated = Punctuated::new();
                     punctuated.push(syn::PathSegment {
-                        ident: enum_ident.unwrap_or(self.0.ident.clone()),
+                        ident: enum_ident.unwrap_or(self.item.ident.clone()),
                         arguments: syn::PathArguments::None,
                     });
                     punctuated.push(syn::PathSegment {
@@ -422,24 +688,7 @@ impl InputEnum {
                     rest: None,
                 });
 
-                let body = if elements.len() == 1 {
-                    let syn::Expr::Path(syn::ExprPath { path, ..}) = elements.first().unwrap() else {
-                        panic!()
-                    };
-                    Box::new(Expr::Path(syn::ExprPath {
-                        attrs: vec![],
-                        qself: None,
-                        path: path.clone(),
-                    }))
-                } else {
-                    Box::new(Expr::Tuple(syn::ExprTuple {
-                        attrs: vec![],
-                        paren_token: token::Paren {
-                            span: group.delim_span(),
-                        },
-                        elems: elements,
-                    }))
-                };
+                let body = Box::new(single_or_tuple(elements));
 
                 (pat, body)
             }
@@ -460,33 +709,127 @@ impl InputEnum {
     }
 }
 
-pub(crate) fn parse_function(
-    ts: proc_macro2::TokenStream,
-    ifn: &mut Option<ItemFn>,
-) -> TokenStream {
-    let r = TokenStream::from(ts);
-    let r2 = r.clone();
-    let pifn = parse_macro_input!(r2 as ItemFn);
-    *ifn = Some(pifn);
-    r
+/// Returns the only element of `elements` or a tuple of all elements.
+fn single_or_tuple(mut elements: Punctuated<Expr, Token![,]>) -> Expr {
+    if elements.len() == 1 {
+        if let Some(single) = elements.pop() {
+            return single.into_value();
+        }
+    }
+
+    let group = proc_macro2::Group::new(
+        proc_macro2::Delimiter::Parenthesis,
+        proc_macro2::TokenStream::new(),
+    );
+    Expr::Tuple(syn::ExprTuple {
+        attrs: vec![],
+        paren_token: token::Paren {
+            span: group.delim_span(),
+        },
+        elems: elements,
+    })
+}
+
+/// `variant_name`, `variant_index`, `VARIANT_COUNT` and `VARIANT_NAMES` for `enum_ident`, which
+/// is the input enum or one of the generated enums.
+fn metadata_items(
+    input: &InputEnum,
+    config: &Config,
+    fc: &FunctionConfig,
+    enum_ident: &Ident,
+) -> syn::Result<(Vec<ItemFn>, Vec<ImplItemConst>)> {
+    let vs = config.function_vis(fc, input);
+    let count = input.variant_count();
+    let names: Vec<String> = input
+        .iter_variants()
+        .map(|v| v.ident.unraw().to_string())
+        .collect();
+
+    let name_match = input.variant_match(Some(enum_ident.clone()), |i| {
+        let name = &names[i];
+        quote! { #name }
+    });
+    let index_match = input.variant_match(Some(enum_ident.clone()), |i| quote! { #i });
+    let variant_name = config.function_ident("variant_name");
+    let variant_index = config.function_ident("variant_index");
+    let functions = vec![
+        parse_function(quote! {
+            #vs fn #variant_name (&self) -> &'static str {
+                #name_match
+            }
+        })?,
+        parse_function(quote! {
+            #vs fn #variant_index (&self) -> usize {
+                #index_match
+            }
+        })?,
+    ];
+
+    let constants = vec![
+        syn::parse2(quote! {
+            #vs const VARIANT_COUNT: usize = #count;
+        })?,
+        syn::parse2(quote! {
+            #vs const VARIANT_NAMES: [&'static str; #count] = [#(#names),*];
+        })?,
+    ];
+    Ok((functions, constants))
+}
+
+/// Compares types by their tokens, since `syn::Type` does not implement `PartialEq` without the
+/// `extra-traits` feature.
+pub(crate) fn same_type(a: &Type, b: &Type) -> bool {
+    a.to_token_stream().to_string() == b.to_token_stream().to_string()
 }
 
+/// Parses a generated function. The function is located at its name, which is the variant it
+/// was generated for, so rustc points there when it conflicts with a user defined function. It
+/// still counts as macro output, e.g. for lints.
+pub(crate) fn parse_function(ts: proc_macro2::TokenStream) -> syn::Result<ItemFn> {
+    let mut function: ItemFn = syn::parse2(ts)?;
+    let span = Span::call_site().located_at(function.sig.ident.span());
+    function.vis = syn::parse2(respan(function.vis.to_token_stream(), span))?;
+    function.sig = syn::parse2(respan(function.sig.to_token_stream(), span))?;
+    function.block = syn::parse2(respan(function.block.to_token_stream(), span))?;
+    Ok(function)
+}
+
+pub(crate) fn respan(tokens: proc_macro2::TokenStream, span: Span) -> proc_macro2::TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut tt| {
+            if let proc_macro2::TokenTree::Group(group) = &tt {
+                let mut respanned =
+                    proc_macro2::Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                tt = respanned.into();
+            }
+            tt.set_span(span);
+            tt
+        })
+        .collect()
+}
+
+/// Copies `attrs` for a generated enum. Derives in `filtered_out` as well as everything belonging
+/// to this crate are removed.
 fn filter_derive_attributes(
     attrs: &[syn::Attribute],
     filtered_out: &[&str],
-) -> Vec<syn::Attribute> {
+) -> syn::Result<Vec<syn::Attribute>> {
     let mut result = vec![];
     for attr in attrs {
         match &attr.meta {
             syn::Meta::List(ml) if ml.path.to_token_stream().to_string() == "derive" => {
                 let punctuated_parser = Punctuated::<syn::Path, Token![,]>::parse_terminated;
-                let punctuated = punctuated_parser.parse2(ml.tokens.clone()).unwrap();
+                let punctuated = punctuated_parser.parse2(ml.tokens.clone())?;
 
                 let mut punctuated_result = Punctuated::<_, Token![,]>::new();
                 for item in punctuated.into_iter() {
-                    let last_segment = item.segments.last().unwrap().ident.to_string();
-                    if filtered_out.contains(&last_segment.as_str()) {
-                        continue;
+                    if let Some(last_segment) = item.segments.last() {
+                        let derive = last_segment.ident.to_string();
+                        if derive == DERIVE_NAME || filtered_out.contains(&derive.as_str()) {
+                            continue;
+                        }
                     }
                     punctuated_result.push(item);
                 }
@@ -501,8 +844,9 @@ fn filter_derive_attributes(
                     }),
                 });
             }
+            _ if attr.path().is_ident(HELPER_ATTRIBUTE) => {}
             _ => result.push(attr.clone()),
         }
     }
-    result
+    Ok(result)
 }
@@ -1,15 +1,22 @@
-use crate::{parse_function, InputEnum};
+use crate::{
+    config::{Config, FunctionConfig, RefEnumConfig},
+    diagnostics::Diagnostics,
+    metadata_items, parse_function, InputEnum,
+};
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::quote;
 use syn::{
     punctuated::Punctuated,
     token::{self, And},
-    ItemFn, Lifetime, Type, TypeReference, TypeTuple, Variant, Visibility,
+    Fields, ImplItemConst, ItemFn, ItemImpl, Lifetime, Type, TypeReference, TypeTuple, Variant,
+    Visibility,
 };
 
 pub(crate) struct RefEnumBuilder<'a> {
     input: &'a InputEnum,
+    config: &'a Config,
+    ref_config: &'a RefEnumConfig,
     mutable: bool,
     generics: syn::Generics,
     lifetime: Lifetime,
@@ -17,29 +24,46 @@ pub(crate) struct RefEnumBuilder<'a> {
     ref_enum_name: Ident,
     variants: Vec<Variant>,
     functions: Vec<ItemFn>,
+    constants: Vec<ImplItemConst>,
+    /// Trait implementations, emitted after the enum.
+    impls: Vec<ItemImpl>,
 }
 
 impl<'a> RefEnumBuilder<'a> {
-    pub(crate) fn new(input: &'a InputEnum, mutable: bool) -> Self {
-        let lifetime = Lifetime::new("'reb", Span::call_site());
+    pub(crate) fn new(
+        input: &'a InputEnum,
+        config: &'a Config,
+        ref_config: &'a RefEnumConfig,
+        mutable: bool,
+    ) -> Self {
+        let lifetime = ref_config
+            .lifetime
+            .clone()
+            .unwrap_or_else(|| Lifetime::new("'reb", Span::call_site()));
 
         let ident = if mutable {
-            Ident::new(format!("{}Mut", input.name()).as_str(), Span::call_site())
+            config.mut_enum_ident(input)
         } else {
-            Ident::new(format!("{}Ref", input.name()).as_str(), Span::call_site())
+            config.ref_enum_ident(input)
         };
         let mut this = Self {
             input,
+            config,
+            ref_config,
             mutable,
-            visibility: input.0.vis.clone(),
+            visibility: ref_config
+                .vis
+                .clone()
+                .unwrap_or_else(|| input.vis().clone()),
             ref_enum_name: ident,
-            generics: input.generics().clone(),
+            generics: input.view_generics(&lifetime),
             lifetime,
             variants: vec![],
             functions: vec![],
+            constants: vec![],
+            impls: vec![],
         };
         this.map_variants();
-        this.adjust_generics();
         this
     }
 
@@ -95,22 +119,44 @@ impl<'a> RefEnumBuilder<'a> {
         })
     }
 
-    fn adjust_generics(&mut self) {
-        self.generics
-            .params
-            .push(syn::GenericParam::Lifetime(syn::LifetimeParam {
-                attrs: vec![],
-                bounds: Punctuated::new(),
-                colon_token: None,
-                lifetime: self.lifetime.clone(),
-            }));
+    /// Generates all RefEnum or MutEnum functions requested in the config.
+    pub(crate) fn generate(&mut self) -> syn::Result<()> {
+        let config = self.config;
+        let mut diagnostics = Diagnostics::default();
+        if let Some(fc) = &config.is {
+            diagnostics.check(self.is_functions(fc));
+        }
+        if let Some(fc) = &config.unwrap {
+            diagnostics.check(self.unwrap_functions(fc));
+        }
+        if let Some(fc) = &config.expect {
+            diagnostics.check(self.expect_functions(fc));
+        }
+        if let Some(fc) = &config.unwrap_or {
+            diagnostics.check(self.unwrap_or_functions(fc));
+        }
+        if let Some(fc) = &config.to_tag {
+            diagnostics.check(self.to_tag_functions(fc));
+        }
+        if let Some(fc) = &config.get {
+            diagnostics.check(self.get_functions(fc));
+        }
+        if let Some(fc) = &config.metadata {
+            diagnostics.check(self.metadata_functions(fc));
+        }
+        if self.ref_config.from {
+            diagnostics.check(self.conversion_impls());
+        }
+        if config.traits.is_some() {
+            diagnostics.check(self.trait_impls());
+        }
+        diagnostics.finish()
     }
 
-    pub(crate) fn is_functions(&mut self) {
-        let vs = self.input.vis();
-        for i in 0..self.input.variant_count() {
-            let nm = self.input.variant_snake_case_name(i);
-            let sp = Ident::new(format!("is_{nm}").as_str(), Span::call_site());
+    pub(crate) fn is_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self.input.helper_variants("is") {
+            let sp = self.config.method_ident(self.input, "is_{}", i);
             let pat = self
                 .input
                 .match_variant(i, Some(self.ref_enum_name.clone())); // Same match clause
@@ -120,64 +166,102 @@ impl<'a> RefEnumBuilder<'a> {
                 }
             };
 
-            let mut ifn = None;
-            parse_function(ts, &mut ifn);
-
-            if let Some(ifn) = ifn {
-                self.functions.push(ifn);
-            } else {
-                panic!()
-            }
+            self.functions.push(parse_function(ts)?);
         }
+        Ok(())
     }
 
-    pub(crate) fn unwrap_functions(&mut self) {
-        let vs = self.input.vis();
-        for i in 0..self.input.variant_count() {
-            let nm = self.input.variant_snake_case_name(i);
-            let sp = Ident::new(format!("unwrap_{nm}").as_str(), Span::call_site());
+    pub(crate) fn unwrap_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        self.panicking_functions(fc, "unwrap")
+    }
+
+    pub(crate) fn expect_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        self.panicking_functions(fc, "expect")
+    }
+
+    /// Generates the `unwrap` or `expect` functions. They return the references of their variant
+    /// and panic for all other variants.
+    fn panicking_functions(&mut self, fc: &FunctionConfig, helper: &str) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        let expect = helper == "expect";
+        let msg = expect.then(|| quote! { , msg: &str });
+        for i in self.input.helper_variants(helper) {
+            let sp = self
+                .config
+                .method_ident(self.input, &format!("{helper}_{{}}"), i);
             let arm = self
                 .input
                 .match_variant_to_tuple(i, Some(self.ref_enum_name.clone()));
+            let panic =
+                self.input
+                    .wrong_variant_panic(&sp, Some(self.ref_enum_name.clone()), expect);
 
             let return_type = self.variant_type(i);
             let lifetime = &self.lifetime;
-            let ts = if self.mutable {
-                quote! {
-                    #vs fn #sp (& #lifetime mut self) -> #return_type {
-                        match self {
-                            #arm
-                            _ => panic!()
-                        }
+            let mutability = self.mutable.then(|| quote! { mut });
+            let ts = quote! {
+                #[track_caller]
+                #vs fn #sp (& #lifetime #mutability self #msg) -> #return_type {
+                    match self {
+                        #arm
+                        _ => #panic
                     }
                 }
-            } else {
-                quote! {
-                    #vs fn #sp (& #lifetime self) -> #return_type {
-                        match self {
-                            #arm
-                            _ => panic!()
-                        }
+            };
+
+            self.functions.push(parse_function(ts)?);
+        }
+        Ok(())
+    }
+
+    /// Generates the `unwrap_{}_or` and `unwrap_{}_or_else` functions. They return the references
+    /// of their variant and the given fallback for all other variants.
+    pub(crate) fn unwrap_or_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self
+            .input
+            .helper_variants("unwrap_or")
+            .filter(|i| self.input.has_fields(*i))
+        {
+            let or = self.config.method_ident(self.input, "unwrap_{}_or", i);
+            let or_else = self.config.method_ident(self.input, "unwrap_{}_or_else", i);
+            let syn::Arm { pat, body, .. } = self
+                .input
+                .match_variant_to_tuple(i, Some(self.ref_enum_name.clone()));
+
+            let return_type = self.variant_type(i);
+            let lifetime = &self.lifetime;
+            let mutability = self.mutable.then(|| quote! { mut });
+            let ts = quote! {
+                #vs fn #or (& #lifetime #mutability self, default: #return_type) -> #return_type {
+                    match self {
+                        #pat => { #body },
+                        _ => default
                     }
                 }
             };
+            self.functions.push(parse_function(ts)?);
 
-            let mut ufn = None;
-            parse_function(ts, &mut ufn);
-
-            if let Some(ufn) = ufn {
-                self.functions.push(ufn);
-            } else {
-                panic!()
-            }
+            let ts = quote! {
+                #vs fn #or_else (
+                    & #lifetime #mutability self,
+                    f: impl FnOnce() -> #return_type,
+                ) -> #return_type {
+                    match self {
+                        #pat => { #body },
+                        _ => f()
+                    }
+                }
+            };
+            self.functions.push(parse_function(ts)?);
         }
+        Ok(())
     }
 
-    pub(crate) fn get_functions(&mut self) {
-        let vs = self.input.vis();
-        for i in 0..self.input.variant_count() {
-            let nm = self.input.variant_snake_case_name(i);
-            let sp = Ident::new(format!("get_{nm}").as_str(), Span::call_site());
+    pub(crate) fn get_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self.input.helper_variants("get") {
+            let sp = self.config.method_ident(self.input, "get_{}", i);
             let syn::Arm { pat, body, .. } = self
                 .input
                 .match_variant_to_tuple(i, Some(self.ref_enum_name.clone()));
@@ -204,26 +288,17 @@ impl<'a> RefEnumBuilder<'a> {
                 }
             };
 
-            let mut ufn = None;
-            parse_function(ts, &mut ufn);
-
-            if let Some(ufn) = ufn {
-                self.functions.push(ufn);
-            } else {
-                panic!()
-            }
+            self.functions.push(parse_function(ts)?);
         }
+        Ok(())
     }
 
     #[allow(clippy::wrong_self_convention)]
-    pub(crate) fn to_tag_functions(&mut self) {
-        let vs = self.input.vis();
-        let sp = Ident::new("to_tag", Span::call_site());
+    pub(crate) fn to_tag_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        let sp = self.config.function_ident("to_tag");
 
-        let tag_ident = Ident::new(
-            format!("{}Tag", self.input.0.ident).as_str(),
-            Span::call_site(),
-        );
+        let tag_ident = self.config.tag_enum_ident(self.input);
 
         let mut arms = vec![];
         for i in 0..self.input.variant_count() {
@@ -256,48 +331,217 @@ impl<'a> RefEnumBuilder<'a> {
             }
         };
 
-        let mut ifn = None;
-        parse_function(ts, &mut ifn);
+        self.functions.push(parse_function(ts)?);
+        Ok(())
+    }
 
-        if let Some(ifn) = ifn {
-            self.functions.push(ifn);
-        } else {
-            panic!()
+    pub(crate) fn metadata_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let (functions, constants) =
+            metadata_items(self.input, self.config, fc, &self.ref_enum_name)?;
+        self.functions.extend(functions);
+        self.constants.extend(constants);
+        Ok(())
+    }
+
+    /// Implements `From<&Enum>` for the RefEnum and `From<&mut Enum>` for the MutEnum. If both
+    /// enums are generated, the MutEnum also converts into the RefEnum.
+    fn conversion_impls(&mut self) -> syn::Result<()> {
+        let input_ident = &self.input.item.ident;
+        let lifetime = &self.lifetime;
+        let mutability = self.mutable.then(|| quote! { mut });
+        let (_, input_type_g, _) = self.input.generics().split_for_impl();
+        let source = quote! { & #lifetime #mutability #input_ident #input_type_g };
+        let conversion = self.conversion_impl(source, input_ident, &self.ref_enum_name)?;
+        self.impls.push(conversion);
+
+        if self.mutable && self.config.ref_enum.is_some() {
+            // Both enums have the generics of the input enum and one lifetime, so the RefEnum
+            // takes the same arguments as this MutEnum.
+            let (_, type_g, _) = self.generics.split_for_impl();
+            let mut_ident = &self.ref_enum_name;
+            let conversion = self.conversion_impl(
+                quote! { #mut_ident #type_g },
+                mut_ident,
+                &self.config.ref_enum_ident(self.input),
+            )?;
+            self.impls.push(conversion);
+        }
+        Ok(())
+    }
+
+    /// `impl From<source> for target`, where both types are one of the input enum, the RefEnum or
+    /// the MutEnum. Each variant is converted to the variant of the same name.
+    fn conversion_impl(
+        &self,
+        source: proc_macro2::TokenStream,
+        source_ident: &Ident,
+        target_ident: &Ident,
+    ) -> syn::Result<ItemImpl> {
+        let (impl_g, type_g, where_g) = self.generics.split_for_impl();
+        let conversion =
+            self.input
+                .conversion_match(quote! { value }, Some(source_ident.clone()), target_ident);
+        syn::parse2(quote! {
+            impl #impl_g From<#source> for #target_ident #type_g #where_g {
+                fn from(value: #source) -> Self {
+                    #conversion
+                }
+            }
+        })
+    }
+
+    /// Implements the traits of `enum-utility-traits`: `Tagged` if there is a TagEnum,
+    /// `HasRefView` and `HasMutView` for the MutEnum, which reborrows its references.
+    fn trait_impls(&mut self) -> syn::Result<()> {
+        let path = self.config.traits_path();
+        let ref_enum_name = &self.ref_enum_name;
+        let (impl_g, type_g, where_g) = self.generics.split_for_impl();
+
+        if self.config.tag_enum.is_some() {
+            let tag_ident = self.config.tag_enum_ident(self.input);
+            let tag_match = self.input.variant_match(Some(ref_enum_name.clone()), |i| {
+                let variant_ident = &self.input.variant(i).ident;
+                quote! { #tag_ident :: #variant_ident }
+            });
+            self.impls.push(syn::parse2(quote! {
+                impl #impl_g #path::Tagged for #ref_enum_name #type_g #where_g {
+                    type Tag = #tag_ident;
+
+                    fn tag(&self) -> #tag_ident {
+                        #tag_match
+                    }
+                }
+            })?);
+        }
+
+        if !self.mutable {
+            return Ok(());
+        }
+        let view_lifetime = Lifetime::new("'view", Span::call_site());
+        let view_generics = self.input.view_generics(&view_lifetime);
+        let (_, view_type_g, _) = view_generics.split_for_impl();
+
+        if self.config.ref_enum.is_some() {
+            let ref_ident = self.config.ref_enum_ident(self.input);
+            let arms = self.reborrow_arms(&ref_ident, false);
+            self.impls.push(syn::parse2(quote! {
+                impl #impl_g #path::HasRefView for #ref_enum_name #type_g #where_g {
+                    type Ref<#view_lifetime> = #ref_ident #view_type_g
+                    where
+                        Self: #view_lifetime;
+
+                    fn view(&self) -> <Self as #path::HasRefView>::Ref<'_> {
+                        match self {
+                            #(#arms ,)*
+                        }
+                    }
+                }
+            })?);
         }
+
+        let arms = self.reborrow_arms(ref_enum_name, true);
+        self.impls.push(syn::parse2(quote! {
+            impl #impl_g #path::HasMutView for #ref_enum_name #type_g #where_g {
+                type Mut<#view_lifetime> = #ref_enum_name #view_type_g
+                where
+                    Self: #view_lifetime;
+
+                fn view_mut(&mut self) -> <Self as #path::HasMutView>::Mut<'_> {
+                    match self {
+                        #(#arms ,)*
+                    }
+                }
+            }
+        })?);
+        Ok(())
     }
 
-    pub(crate) fn token_stream(&self) -> TokenStream {
+    /// Match arms converting a borrowed MutEnum into `target` by reborrowing each reference.
+    fn reborrow_arms(&self, target: &Ident, mutable: bool) -> Vec<proc_macro2::TokenStream> {
+        let source = &self.ref_enum_name;
+        let reborrow = if mutable {
+            quote! { &mut ** }
+        } else {
+            quote! { &** }
+        };
+        self.variants
+            .iter()
+            .map(|variant| {
+                let variant_ident = &variant.ident;
+                let bindings: Vec<Ident> = variant
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| match &field.ident {
+                        Some(ident) => ident.clone(),
+                        None => Ident::new(&format!("e{index}"), Span::call_site()),
+                    })
+                    .collect();
+                match &variant.fields {
+                    Fields::Unit => {
+                        quote! { #source :: #variant_ident => #target :: #variant_ident }
+                    }
+                    Fields::Unnamed(_) => quote! {
+                        #source :: #variant_ident ( #(#bindings),* ) =>
+                            #target :: #variant_ident ( #(#reborrow #bindings),* )
+                    },
+                    Fields::Named(_) => quote! {
+                        #source :: #variant_ident { #(#bindings),* } =>
+                            #target :: #variant_ident { #(#bindings: #reborrow #bindings),* }
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Names of all generated functions.
+    pub(crate) fn function_idents(&self) -> impl Iterator<Item = &Ident> {
+        self.functions.iter().map(|f| &f.sig.ident)
+    }
+
+    pub(crate) fn token_stream(&self) -> syn::Result<TokenStream> {
         let visibility = &self.visibility;
         let ref_enum_name = &self.ref_enum_name;
         let ref_enum_variants = &self.variants;
 
         let attributes = self.input.attributes();
         let attributes = if self.mutable {
-            super::filter_derive_attributes(attributes.as_slice(), &["Clone", "Copy"])
+            super::filter_derive_attributes(attributes.as_slice(), &["Clone", "Copy"])?
         } else {
-            super::filter_derive_attributes(attributes.as_slice(), &[])
+            super::filter_derive_attributes(attributes.as_slice(), &[])?
         };
 
+        let derives = &self.ref_config.derives;
+        let derives = (!derives.is_empty()).then(|| quote! { #[derive(#(#derives),*)] });
+
         let generics = &self.generics;
         let ref_enum = quote! {
             #(#attributes)*
+            #derives
             #visibility enum #ref_enum_name #generics {
                 #(#ref_enum_variants ,)*
             }
         };
         let mut ref_enum_stream = TokenStream::from(ref_enum);
 
-        if !self.functions.is_empty() {
+        if !self.functions.is_empty() || !self.constants.is_empty() {
             let (impl_g, type_g, where_g) = self.generics.split_for_impl();
             let functions = &self.functions;
+            let allow = self.config.non_snake_case_attribute();
+            let constants = &self.constants;
             let ref_functions = quote! {
+                #allow
                 impl #impl_g #ref_enum_name #type_g #where_g {
+                    #(#constants)*
                     #(#functions)*
                 }
             };
             ref_enum_stream.extend([TokenStream::from(ref_functions)]);
         }
 
-        ref_enum_stream
+        let impls = &self.impls;
+        ref_enum_stream.extend([TokenStream::from(quote! { #(#impls)* })]);
+
+        Ok(ref_enum_stream)
     }
 }
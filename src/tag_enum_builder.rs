@@ -1,28 +1,48 @@
-use crate::{parse_function, InputEnum};
+use crate::{
+    case,
+    config::{Config, FunctionConfig, TagEnumConfig, TagStringsConfig},
+    diagnostics::Diagnostics,
+    metadata_items, parse_function, InputEnum,
+};
 
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::quote;
-use syn::{Fields, ItemFn, Variant, Visibility};
+use syn::{ext::IdentExt, Fields, ImplItemConst, ItemFn, Path, Variant, Visibility};
+
+const DEFAULT_DERIVES: [&str; 6] = ["Clone", "Copy", "Debug", "PartialEq", "Eq", "Hash"];
 
 pub(crate) struct TagEnumBuilder<'a> {
     input: &'a InputEnum,
+    config: &'a Config,
+    tag_config: &'a TagEnumConfig,
     visibility: Visibility,
     tag_enum_name: Ident,
     variants: Vec<Variant>,
     functions: Vec<ItemFn>,
+    constants: Vec<ImplItemConst>,
 }
 
 impl<'a> TagEnumBuilder<'a> {
-    pub(crate) fn new(input: &'a InputEnum) -> Self {
-        let vis = input.vis().clone();
-        let tag_enum_name = Ident::new(format!("{}Tag", input.name()).as_str(), Span::call_site());
+    pub(crate) fn new(
+        input: &'a InputEnum,
+        config: &'a Config,
+        tag_config: &'a TagEnumConfig,
+    ) -> Self {
+        let vis = tag_config
+            .vis
+            .clone()
+            .unwrap_or_else(|| input.vis().clone());
+        let tag_enum_name = config.tag_enum_ident(input);
         let mut this = Self {
             input,
+            config,
+            tag_config,
             visibility: vis,
             tag_enum_name,
             variants: vec![],
             functions: vec![],
+            constants: vec![],
         };
         this.map_variants();
         this
@@ -39,11 +59,26 @@ impl<'a> TagEnumBuilder<'a> {
         }
     }
 
-    pub(crate) fn is_functions(&mut self) {
-        let vs = self.input.vis();
-        for i in 0..self.input.variant_count() {
-            let nm = self.input.variant_snake_case_name(i);
-            let sp = Ident::new(format!("is_{nm}").as_str(), Span::call_site());
+    /// Generates all TagEnum functions requested in the config.
+    pub(crate) fn generate(&mut self) -> syn::Result<()> {
+        let mut diagnostics = Diagnostics::default();
+        let config = self.config;
+        if let Some(fc) = &config.is {
+            diagnostics.check(self.is_functions(fc));
+        }
+        if let Some(fc) = &config.metadata {
+            diagnostics.check(self.metadata_functions(fc));
+        }
+        if let Some(sc) = &self.tag_config.strings {
+            diagnostics.check(self.as_str_function(sc));
+        }
+        diagnostics.finish()
+    }
+
+    pub(crate) fn is_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let vs = self.config.function_vis(fc, self.input);
+        for i in self.input.helper_variants("is") {
+            let sp = self.config.method_ident(self.input, "is_{}", i);
 
             let eident = &self.tag_enum_name;
             let vident = &self.variants[i].ident;
@@ -54,39 +89,189 @@ impl<'a> TagEnumBuilder<'a> {
                 }
             };
 
-            let mut ifn = None;
-            parse_function(ts, &mut ifn);
+            self.functions.push(parse_function(ts)?);
+        }
+        Ok(())
+    }
+
+    /// String of variant `i`, used by `as_str`, `Display` and `FromStr`.
+    fn variant_string(&self, sc: &TagStringsConfig, i: usize) -> String {
+        let name = self.variants[i].ident.unraw().to_string();
+        match sc.rename_all {
+            Some(rule) => rule.apply(&case::words(&name)),
+            None => name,
+        }
+    }
 
-            if let Some(ifn) = ifn {
-                self.functions.push(ifn);
-            } else {
-                panic!()
+    /// Generates `as_str`. Variants whose strings can not be told apart when parsing are
+    /// reported.
+    fn as_str_function(&mut self, sc: &TagStringsConfig) -> syn::Result<()> {
+        let mut diagnostics = Diagnostics::default();
+        let strings: Vec<String> = (0..self.variants.len())
+            .map(|i| self.variant_string(sc, i))
+            .collect();
+        for (i, string) in strings.iter().enumerate() {
+            let same = |other: &String| match sc.case_insensitive {
+                true => other.eq_ignore_ascii_case(string),
+                false => other == string,
+            };
+            if let Some(j) = strings[..i].iter().position(same) {
+                let mut error = syn::Error::new(
+                    self.variants[j].ident.span(),
+                    format!("the TagEnum string `{}` is used more than once", strings[j]),
+                );
+                error.combine(syn::Error::new(
+                    self.variants[i].ident.span(),
+                    format!("`{string}` is also used here"),
+                ));
+                diagnostics.push(error);
             }
         }
+
+        let vs = &self.visibility;
+        let sp = self.config.function_ident("as_str");
+        let tag_match = self
+            .input
+            .variant_match(Some(self.tag_enum_name.clone()), |i| {
+                let string = &strings[i];
+                quote! { #string }
+            });
+        let ts = quote! {
+            #vs fn #sp (self) -> &'static str {
+                #tag_match
+            }
+        };
+        self.functions.push(parse_function(ts)?);
+        diagnostics.finish()
+    }
+
+    fn derives(&self) -> Vec<Path> {
+        let mut derives: Vec<Path> = DEFAULT_DERIVES
+            .iter()
+            .map(|d| Ident::new(d, Span::call_site()).into())
+            .collect();
+        for derive in &self.tag_config.derives {
+            let last_segment = derive.segments.last().map(|s| s.ident.to_string());
+            if !DEFAULT_DERIVES.contains(&last_segment.as_deref().unwrap_or_default()) {
+                derives.push(derive.clone());
+            }
+        }
+        derives
+    }
+
+    pub(crate) fn metadata_functions(&mut self, fc: &FunctionConfig) -> syn::Result<()> {
+        let (functions, constants) =
+            metadata_items(self.input, self.config, fc, &self.tag_enum_name)?;
+        self.functions.extend(functions);
+        self.constants.extend(constants);
+        Ok(())
+    }
+
+    /// Names of all generated functions.
+    pub(crate) fn function_idents(&self) -> impl Iterator<Item = &Ident> {
+        self.functions.iter().map(|f| &f.sig.ident)
     }
 
     pub(crate) fn token_stream(&self) -> TokenStream {
         let visibility = &self.visibility;
         let tag_enum_name = &self.tag_enum_name;
         let tag_enum_variants = &self.variants;
+        let derives = self.derives();
         let tag_enum = quote! {
-            #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+            #[derive(#(#derives),*)]
             #visibility enum #tag_enum_name {
                 #(#tag_enum_variants ,)*
             }
         };
         let mut tag_enum_stream = TokenStream::from(tag_enum);
 
-        if !self.functions.is_empty() {
+        if !self.functions.is_empty() || !self.constants.is_empty() {
             let functions = &self.functions;
+            let allow = self.config.non_snake_case_attribute();
+            let constants = &self.constants;
             let tag_functions = quote! {
+                #allow
                 impl #tag_enum_name {
+                    #(#constants)*
                     #(#functions)*
                 }
             };
             tag_enum_stream.extend([TokenStream::from(tag_functions)]);
         }
 
+        if let Some(sc) = &self.tag_config.strings {
+            tag_enum_stream.extend([self.string_impls(sc)]);
+        }
+
         tag_enum_stream
     }
+
+    /// `Display` and `FromStr` for the TagEnum, using the strings of `as_str`, and the error of
+    /// `FromStr`.
+    fn string_impls(&self, sc: &TagStringsConfig) -> TokenStream {
+        let visibility = &self.visibility;
+        let tag_enum_name = &self.tag_enum_name;
+        let error_name = self.config.parse_tag_error_ident(self.input);
+        let as_str = self.config.function_ident("as_str");
+        let strings: Vec<String> = (0..self.variants.len())
+            .map(|i| self.variant_string(sc, i))
+            .collect();
+        let variant_names = self.variants.iter().map(|v| &v.ident);
+
+        let parse = if sc.case_insensitive {
+            quote! {
+                #(
+                    if s.eq_ignore_ascii_case(#strings) {
+                        return Ok(#tag_enum_name :: #variant_names);
+                    }
+                )*
+            }
+        } else {
+            quote! {
+                match s {
+                    #(#strings => return Ok(#tag_enum_name :: #variant_names),)*
+                    _ => {}
+                }
+            }
+        };
+
+        let error_doc = format!("Error returned when parsing a [`{tag_enum_name}`] fails.");
+        let display = format!(
+            "unknown {} `{{}}`, expected one of: {}",
+            tag_enum_name.unraw(),
+            strings.join(", ")
+        );
+        let string_impls = quote! {
+            impl ::core::fmt::Display for #tag_enum_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.pad(self.#as_str())
+                }
+            }
+
+            impl ::core::str::FromStr for #tag_enum_name {
+                type Err = #error_name;
+
+                fn from_str(s: &str) -> ::core::result::Result<Self, #error_name> {
+                    #parse
+                    Err(#error_name { value: s.to_string() })
+                }
+            }
+
+            #[doc = #error_doc]
+            #[derive(Clone, Debug, PartialEq, Eq)]
+            #visibility struct #error_name {
+                /// The string which does not name a variant.
+                pub value: String,
+            }
+
+            impl ::core::fmt::Display for #error_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    write!(f, #display, self.value)
+                }
+            }
+
+            impl ::std::error::Error for #error_name {}
+        };
+        TokenStream::from(string_impls)
+    }
 }
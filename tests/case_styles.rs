@@ -0,0 +1,46 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(is, unwrap, get_ref)]
+enum Message {
+    HTTPServer(u16),
+    V2Beta,
+    r#Type(&'static str),
+    Self_,
+    IOError,
+}
+
+#[generate_enum_helper(TagEnum, RefEnum, is, unwrap, to_tag, as_ref, rename_all = "camelCase")]
+enum Camel {
+    HTTPServer(u16),
+    V2Beta,
+}
+
+#[generate_enum_helper(is, get, rename_all = "SCREAMING_SNAKE_CASE")]
+enum Screaming {
+    HTTPServer(u16),
+}
+
+#[test]
+fn case_styles_test() {
+    let v1 = Message::HTTPServer(80);
+    assert!(v1.is_http_server());
+    assert_eq!(v1.get_ref_http_server(), Some(&80));
+    assert!(Message::V2Beta.is_v2_beta());
+    assert!(Message::Type("a").is_type());
+    assert_eq!(Message::Type("a").unwrap_type(), "a");
+    assert!(Message::Self_.is_self());
+    assert!(Message::IOError.is_io_error());
+
+    let v2 = Camel::HTTPServer(443);
+    assert!(v2.isHttpServer());
+    assert!(v2.asRef().isHttpServer());
+    assert_eq!(v2.toTag(), CamelTag::HTTPServer);
+    assert_eq!(v2.unwrapHttpServer(), 443);
+    assert!(Camel::V2Beta.isV2Beta());
+
+    let v3 = Screaming::HTTPServer(8080);
+    assert!(v3.IS_HTTP_SERVER());
+    assert_eq!(v3.GET_HTTP_SERVER(), Some(8080));
+}
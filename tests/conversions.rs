@@ -0,0 +1,64 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[derive(Debug)]
+#[generate_enum_helper(
+    RefEnum(from),
+    MutEnum(lifetime = 'm, from),
+    as_ref(name = as_view),
+    as_mut(name = as_view_mut),
+    unwrap
+)]
+enum MyEnum<T> {
+    Value(T),
+    Pair(i32, i32),
+    Point { x: i32, y: i32 },
+    Empty,
+}
+
+// Without `from` the conversions are left to the user.
+#[generate_enum_helper(RefEnum, is)]
+enum Plain {
+    Int32(i32),
+    Empty,
+}
+
+impl<'reb> From<&'reb Plain> for PlainRef<'reb> {
+    fn from(_: &'reb Plain) -> Self {
+        PlainRef::Empty
+    }
+}
+
+fn view_value<'a>(e: impl Into<MyEnumRef<'a, u8>>) -> Option<u8> {
+    match e.into() {
+        MyEnumRef::Value(v) => Some(*v),
+        _ => None,
+    }
+}
+
+#[test]
+fn conversions_test() {
+    let mut e = MyEnum::Value(5u8);
+    assert_eq!(view_value(&e), Some(5));
+    assert_eq!(view_value(MyEnumRef::from(&e)), Some(5));
+
+    *MyEnumMut::from(&mut e).unwrap_value() = 6;
+    assert_eq!(view_value(e.as_view_mut()), Some(6));
+    assert_eq!(view_value(&MyEnum::Empty), None);
+
+    let mut point = MyEnum::<u8>::Point { x: 1, y: 2 };
+    let view = MyEnumRef::from(point.as_view_mut());
+    let (x, y) = view.unwrap_point();
+    assert_eq!((*x, *y), (1, 2));
+    assert_eq!(point.as_view().unwrap_point(), (&1, &2));
+
+    let pair = MyEnum::<u8>::Pair(3, 4);
+    let view: MyEnumRef<u8> = (&pair).into();
+    assert_eq!(view.unwrap_pair(), (&3, &4));
+}
+
+#[test]
+fn user_conversion_test() {
+    assert!(PlainRef::from(&Plain::Int32(1)).is_empty());
+}
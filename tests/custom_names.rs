@@ -0,0 +1,40 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(
+    TagEnum(name = ShapeKind),
+    RefEnum(name = "ShapeView"),
+    MutEnum(name = ShapeViewMut),
+    is,
+    unwrap,
+    to_tag,
+    as_ref,
+    as_mut
+)]
+enum Shape {
+    Circle { radius: f32 },
+    Square(f32),
+}
+
+#[test]
+fn custom_names_test() {
+    let mut s1 = Shape::Circle { radius: 1.0 };
+    let s2 = Shape::Square(2.0);
+
+    assert_eq!(s1.to_tag(), ShapeKind::Circle);
+    assert_eq!(s2.to_tag(), ShapeKind::Square);
+    assert!(ShapeKind::Circle.is_circle());
+
+    let r1: ShapeView<'_> = s1.as_ref();
+    assert_eq!(r1.to_tag(), ShapeKind::Circle);
+    assert_eq!(*r1.unwrap_circle(), 1.0);
+
+    let m1: ShapeViewMut<'_> = s1.as_mut();
+    assert!(m1.is_circle());
+    let ShapeViewMut::Circle { radius } = m1 else {
+        panic!()
+    };
+    *radius = 3.0;
+    assert_eq!(s1.unwrap_circle(), 3.0);
+}
@@ -0,0 +1,34 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+// `as_ref` only requires `RefEnum`, the TagEnum is not needed.
+#[generate_enum_helper(RefEnum, as_ref, is)]
+enum MyEnum {
+    Int32(i32),
+    Float(f32),
+}
+
+// Excluding `TagEnum` also leaves out `to_tag`, which the preset enabled.
+#[generate_enum_helper(all, !TagEnum)]
+enum Untagged {
+    Int32(i32),
+    Float(f32),
+}
+
+#[test]
+fn dependencies_test() {
+    let v1 = MyEnum::Int32(1);
+    assert!(v1.as_ref().is_int32());
+    let MyEnumRef::Int32(i) = v1.as_ref() else {
+        panic!()
+    };
+    assert_eq!(*i, 1);
+}
+
+#[test]
+fn excluded_dependency_test() {
+    let v1 = Untagged::Float(1.0);
+    assert!(v1.as_ref().is_float());
+    assert_eq!(v1.try_unwrap_float().ok(), Some(1.0));
+}
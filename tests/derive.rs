@@ -0,0 +1,51 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::EnumHelper;
+
+#[derive(Clone, Debug, EnumHelper, PartialEq)]
+#[enum_helper(TagEnum, RefEnum(derive(Debug, PartialEq)), MutEnum(name = MyEnumView), is, unwrap)]
+#[enum_helper(to_tag, as_ref, as_mut, get_ref)]
+enum MyEnum {
+    #[enum_helper(rename = "int")]
+    Int32(i32),
+    Float {
+        float: f32,
+    },
+}
+
+#[test]
+fn derive_test() {
+    let mut v1 = MyEnum::Int32(1);
+    let v2 = MyEnum::Float { float: 2.0 };
+
+    assert!(v1.is_int());
+    assert!(!v2.is_int());
+    assert_eq!(v2.to_tag(), MyEnumTag::Float);
+    assert_eq!(v1.get_ref_int(), Some(&1));
+    assert_eq!(v1.as_ref(), MyEnumRef::Int32(&1));
+    assert_eq!(v1.as_ref().to_tag(), MyEnumTag::Int32);
+
+    let MyEnumView::Int32(i) = v1.as_mut() else {
+        panic!()
+    };
+    *i = 5;
+    assert_eq!(v1.clone().unwrap_int(), 5);
+    assert_eq!(v2.unwrap_float(), 2.0);
+}
+
+// `#[default]` belongs to `Default`, which is not derived for the generated enums.
+#[derive(Debug, Default, EnumHelper)]
+#[enum_helper(TagEnum, RefEnum, MutEnum, to_tag)]
+enum WithDefault {
+    /// Documentation is copied.
+    #[default]
+    A,
+    B(u8),
+}
+
+#[test]
+fn default_attribute_test() {
+    assert_eq!(WithDefault::default().to_tag(), WithDefaultTag::A);
+    assert!(matches!(WithDefaultRef::B(&1), WithDefaultRef::B(1)));
+    assert!(matches!(WithDefaultMut::A, WithDefaultMut::A));
+}
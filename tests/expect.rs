@@ -0,0 +1,69 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(
+    RefEnum, MutEnum, unwrap, unwrap_ref, expect, expect_ref, expect_mut, as_ref, as_mut
+)]
+enum MyEnum {
+    Int32(i32),
+    Float(f32),
+    Point { x: i32, y: i32 },
+}
+
+#[test]
+fn expect_test() {
+    let mut v1 = MyEnum::Point { x: 1, y: 2 };
+    assert_eq!(v1.expect_ref_point("point"), (&1, &2));
+    *v1.expect_mut_point("point").0 = 3;
+    assert_eq!(v1.as_ref().expect_point("point"), (&3, &2));
+    *v1.as_mut().expect_point("point").1 = 4;
+    assert_eq!(v1.expect_point("point"), (3, 4));
+}
+
+#[test]
+#[should_panic(expected = "called unwrap_int32 on MyEnum::Float")]
+fn unwrap_message_test() {
+    MyEnum::Float(1.0).unwrap_int32();
+}
+
+#[test]
+#[should_panic(expected = "called unwrap_ref_float on MyEnum::Point")]
+fn unwrap_ref_message_test() {
+    MyEnum::Point { x: 1, y: 2 }.unwrap_ref_float();
+}
+
+#[test]
+#[should_panic(expected = "called unwrap_float on MyEnumRef::Int32")]
+fn ref_enum_unwrap_message_test() {
+    MyEnum::Int32(1).as_ref().unwrap_float();
+}
+
+#[test]
+#[should_panic(expected = "no int: found MyEnum::Float")]
+fn expect_message_test() {
+    MyEnum::Float(1.0).expect_int32("no int");
+}
+
+#[test]
+#[should_panic(expected = "no float: found MyEnumMut::Int32")]
+fn mut_enum_expect_message_test() {
+    MyEnum::Int32(1).as_mut().expect_float("no float");
+}
+
+thread_local! {
+    static PANIC_LINE: std::cell::Cell<u32> = const { std::cell::Cell::new(0) };
+}
+
+#[test]
+fn track_caller_test() {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        PANIC_LINE.set(info.location().map_or(0, |l| l.line()));
+        hook(info);
+    }));
+    let line = line!() + 1;
+    let result = std::panic::catch_unwind(|| MyEnum::Int32(1).expect_float("no float"));
+    assert!(result.is_err());
+    assert_eq!(PANIC_LINE.get(), line);
+}
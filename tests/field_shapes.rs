@@ -0,0 +1,35 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(
+    TagEnum, RefEnum, MutEnum, is, unwrap, get, to_tag, unwrap_ref, unwrap_mut, as_ref, as_mut,
+    get_ref, get_mut
+)]
+enum MyEnum {
+    Str(&'static str),
+    Array([u8; 2]),
+    Tuple((i32, i32)),
+    Unit,
+}
+
+#[test]
+fn field_shapes_test() {
+    let v1 = MyEnum::Str("hello");
+    let mut v2 = MyEnum::Array([1, 2]);
+    let mut v3 = MyEnum::Tuple((3, 4));
+    let v4 = MyEnum::Unit;
+
+    let _: &&str = v1.unwrap_ref_str();
+    let _: &[u8; 2] = v2.unwrap_ref_array();
+    let _: &(i32, i32) = v3.unwrap_ref_tuple();
+    let _: () = v4.unwrap_ref_unit();
+
+    v2.unwrap_mut_array()[0] = 5;
+    assert_eq!(v2.get_ref_array(), Some(&[5, 2]));
+
+    v3.get_mut_tuple().unwrap().1 = 6;
+    assert_eq!(v3.get_mut_array(), None);
+    assert_eq!(v3.unwrap_tuple(), (3, 6));
+    assert_eq!(v1.get_str(), Some("hello"));
+}
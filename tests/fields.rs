@@ -0,0 +1,33 @@
+#![allow(dead_code, non_snake_case)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(fields, fields_mut)]
+enum MyEnum {
+    Int32(i32),
+    Config {
+        name: String,
+        r#type: u8,
+        maxValue: u32,
+    },
+}
+
+#[test]
+fn fields_test() {
+    let mut v1 = MyEnum::Config {
+        name: "a".to_string(),
+        r#type: 1,
+        maxValue: 2,
+    };
+    assert_eq!(v1.config_name().map(String::as_str), Some("a"));
+    assert_eq!(v1.config_type(), Some(&1));
+    assert_eq!(v1.config_max_value(), Some(&2));
+    *v1.config_type_mut().unwrap() = 3;
+    v1.config_name_mut().unwrap().push('b');
+    assert_eq!(v1.config_type(), Some(&3));
+    assert_eq!(v1.config_name().map(String::as_str), Some("ab"));
+
+    let mut v2 = MyEnum::Int32(1);
+    assert_eq!(v2.config_name(), None);
+    assert_eq!(v2.config_max_value_mut(), None);
+}
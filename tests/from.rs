@@ -0,0 +1,74 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[derive(Debug, PartialEq)]
+#[generate_enum_helper(from)]
+enum MyEnum {
+    Int32(i32),
+    Text(String),
+    Point { x: i32, y: i32 },
+    Pair(u8, u8),
+    Empty,
+}
+
+#[derive(Debug, PartialEq)]
+#[generate_enum_helper(from(skip_ambiguous))]
+enum Ambiguous<'a, T> {
+    First(u8),
+    Second(u8),
+    Generic(T),
+    Name(&'a str),
+    Values(Vec<T>),
+}
+
+#[derive(Debug, PartialEq)]
+#[generate_enum_helper(from)]
+enum Skipped {
+    Int32(i32),
+    #[enum_helper(skip(from))]
+    Other(i32),
+}
+
+#[derive(Debug, PartialEq)]
+enum Level {
+    Error,
+    Warn,
+}
+
+// `Self::Error` would be ambiguous with the variant `Level::Error`.
+#[derive(Debug, PartialEq)]
+#[generate_enum_helper(from)]
+enum Event {
+    Log(Level),
+    Code(u32),
+}
+
+#[test]
+fn from_test() {
+    let e: MyEnum = 5i32.into();
+    assert_eq!(e, MyEnum::Int32(5));
+    assert_eq!(MyEnum::from("a".to_string()), MyEnum::Text("a".to_string()));
+
+    assert_eq!(i32::try_from(MyEnum::Int32(5)), Ok(5));
+    assert_eq!(String::try_from(MyEnum::Int32(5)), Err(MyEnum::Int32(5)));
+    let text: Result<String, _> = MyEnum::Text("a".to_string()).try_into();
+    assert_eq!(text, Ok("a".to_string()));
+}
+
+#[test]
+fn ambiguous_test() {
+    assert_eq!(Ambiguous::<u8>::from("a"), Ambiguous::Name("a"));
+    assert_eq!(Ambiguous::from(vec![1u8]), Ambiguous::Values(vec![1]));
+    assert_eq!(<&str>::try_from(Ambiguous::<u8>::Name("a")), Ok("a"));
+
+    assert_eq!(Skipped::from(1), Skipped::Int32(1));
+    assert_eq!(i32::try_from(Skipped::Other(1)), Err(Skipped::Other(1)));
+}
+
+#[test]
+fn error_variant_test() {
+    assert_eq!(Event::from(Level::Error), Event::Log(Level::Error));
+    assert_eq!(Level::try_from(Event::Log(Level::Warn)), Ok(Level::Warn));
+    assert_eq!(Level::try_from(Event::Code(1)), Err(Event::Code(1)));
+}
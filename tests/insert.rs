@@ -0,0 +1,34 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[derive(Debug, PartialEq)]
+#[generate_enum_helper(insert, get_or_insert)]
+enum State {
+    Idle,
+    Counting(u32),
+    Buffering { data: Vec<u8>, limit: usize },
+}
+
+#[test]
+fn insert_test() {
+    let mut state = State::Idle;
+    *state.get_or_insert_counting_with(|| 0) += 1;
+    *state.get_or_insert_counting(10) += 1;
+    assert_eq!(state, State::Counting(2));
+
+    let (data, limit) = state.get_or_insert_buffering_with(|| (vec![], 4));
+    data.push(*limit as u8);
+    let (data, _) = state.get_or_insert_buffering((vec![9], 1));
+    data.push(5);
+    assert_eq!(
+        state,
+        State::Buffering {
+            data: vec![4, 5],
+            limit: 4
+        }
+    );
+
+    *state.insert_counting(7) += 1;
+    assert_eq!(state, State::Counting(8));
+}
@@ -0,0 +1,31 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[derive(Debug, PartialEq)]
+#[generate_enum_helper(into)]
+enum MyEnum {
+    Int32(i32),
+    Text(String),
+    Point { x: i32, y: i32 },
+    Empty,
+}
+
+fn describe(v: MyEnum) -> String {
+    v.into_int32()
+        .map(|i| format!("int {i}"))
+        .or_else(|v| v.into_text())
+        .or_else(|v| v.into_point().map(|(x, y)| format!("point {x} {y}")))
+        .unwrap_or_else(|v| format!("{v:?}"))
+}
+
+#[test]
+fn into_test() {
+    assert_eq!(describe(MyEnum::Int32(1)), "int 1");
+    assert_eq!(describe(MyEnum::Text("a".to_string())), "a");
+    assert_eq!(describe(MyEnum::Point { x: 1, y: 2 }), "point 1 2");
+    assert_eq!(describe(MyEnum::Empty), "Empty");
+
+    assert_eq!(MyEnum::Empty.into_empty(), Ok(()));
+    assert_eq!(MyEnum::Int32(3).into_empty(), Err(MyEnum::Int32(3)));
+}
@@ -0,0 +1,40 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[derive(Debug, PartialEq)]
+#[generate_enum_helper(map, update)]
+enum MyEnum {
+    Int32(i32),
+    Point { x: i32, y: i32 },
+    Pair(String, usize),
+    Named { f: i32 },
+    Empty,
+}
+
+#[test]
+fn map_test() {
+    assert_eq!(MyEnum::Int32(1).map_int32(|x| x + 1), MyEnum::Int32(2));
+    assert_eq!(MyEnum::Empty.map_int32(|x| x + 1), MyEnum::Empty);
+    assert_eq!(
+        MyEnum::Point { x: 1, y: 2 }.map_point(|(x, y)| (y, x)),
+        MyEnum::Point { x: 2, y: 1 }
+    );
+    assert_eq!(
+        MyEnum::Pair("a".to_string(), 1).map_pair(|(s, n)| (s + "b", n + 1)),
+        MyEnum::Pair("ab".to_string(), 2)
+    );
+    // A field named like the closure argument does not shadow it.
+    assert_eq!(
+        MyEnum::Named { f: 1 }.map_named(|f| f * 3),
+        MyEnum::Named { f: 3 }
+    );
+
+    let mut v1 = MyEnum::Point { x: 1, y: 2 };
+    v1.update_point(|(x, y)| *x += *y);
+    v1.update_int32(|x| *x = 0);
+    assert_eq!(v1, MyEnum::Point { x: 3, y: 2 });
+    let mut v2 = MyEnum::Named { f: 1 };
+    v2.update_named(|f| *f += 1);
+    assert_eq!(v2, MyEnum::Named { f: 2 });
+}
@@ -0,0 +1,28 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(TagEnum, RefEnum, MutEnum, to_tag, as_ref, as_mut, metadata)]
+enum MyEnum<T> {
+    Int32(i32),
+    Point { x: T, y: T },
+    r#Type,
+}
+
+#[test]
+fn metadata_test() {
+    let mut v1 = MyEnum::Point { x: 1u8, y: 2 };
+    assert_eq!(v1.variant_name(), "Point");
+    assert_eq!(v1.variant_index(), 1);
+    assert_eq!(v1.as_ref().variant_name(), "Point");
+    assert_eq!(v1.as_mut().variant_index(), 1);
+    assert_eq!(v1.to_tag().variant_name(), "Point");
+    assert_eq!(MyEnum::<u8>::Type.variant_name(), "Type");
+    assert_eq!(MyEnumTag::Int32.variant_index(), 0);
+
+    assert_eq!(MyEnum::<u8>::VARIANT_COUNT, 3);
+    assert_eq!(MyEnum::<u8>::VARIANT_NAMES, ["Int32", "Point", "Type"]);
+    assert_eq!(MyEnumTag::VARIANT_COUNT, 3);
+    assert_eq!(MyEnumRef::<u8>::VARIANT_NAMES[2], "Type");
+    assert_eq!(MyEnumMut::<u8>::VARIANT_COUNT, 3);
+}
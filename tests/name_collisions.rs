@@ -0,0 +1,27 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+// `Ab` and `AB` both generate `is_ab`, which is a compile error. Renaming one of them resolves
+// the collision. The same holds for `get_ref_foo`, which is generated by `get` for `RefFoo` and
+// by `get_ref` for `Foo`.
+#[generate_enum_helper(is, get, get_ref)]
+enum MyEnum {
+    Ab(i32),
+    #[enum_helper(rename = "upper_ab")]
+    AB(i32),
+    #[enum_helper(skip(get))]
+    RefFoo(i32),
+    Foo(i32),
+}
+
+#[test]
+fn name_collisions_test() {
+    let v1 = MyEnum::AB(1);
+    assert!(v1.is_upper_ab());
+    assert!(!v1.is_ab());
+    assert_eq!(v1.get_ref_upper_ab(), Some(&1));
+    assert_eq!(MyEnum::RefFoo(2).get_ref_ref_foo(), Some(&2));
+    assert_eq!(MyEnum::Foo(3).get_ref_foo(), Some(&3));
+    assert!(MyEnum::Foo(2).is_foo());
+}
@@ -0,0 +1,34 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[derive(Debug, PartialEq)]
+#[generate_enum_helper(new)]
+enum MyEnum {
+    Int32(i32),
+    Point { x: i32, y: i32 },
+    Empty,
+}
+
+#[derive(Debug, PartialEq)]
+#[generate_enum_helper(new(into))]
+enum Message<T> {
+    Text(String),
+    Labeled { label: String, value: T },
+}
+
+#[test]
+fn new_test() {
+    assert_eq!(MyEnum::new_int32(1), MyEnum::Int32(1));
+    assert_eq!(MyEnum::new_point(1, 2), MyEnum::Point { x: 1, y: 2 });
+    assert_eq!(MyEnum::new_empty(), MyEnum::Empty);
+
+    assert_eq!(Message::<u8>::new_text("a"), Message::Text("a".to_string()));
+    assert_eq!(
+        Message::new_labeled("b", 1u8),
+        Message::Labeled {
+            label: "b".to_string(),
+            value: 1
+        }
+    );
+}
@@ -0,0 +1,38 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(
+    TagEnum(derive(PartialOrd, Ord, Hash)),
+    RefEnum(lifetime = 'a),
+    MutEnum(lifetime = 'b),
+    is,
+    unwrap(track_caller),
+    unwrap_ref(track_caller),
+    to_tag,
+    as_ref,
+    as_mut
+)]
+enum MyEnum {
+    Int32(i32),
+    Float(f32),
+}
+
+#[test]
+fn options_test() {
+    let mut v1 = MyEnum::Int32(1);
+    let v2 = MyEnum::Float(2.0);
+
+    assert!(v1.to_tag() < v2.to_tag());
+    assert_eq!(MyEnumTag::Int32.max(MyEnumTag::Float), MyEnumTag::Float);
+
+    let r1: MyEnumRef<'_> = v1.as_ref();
+    assert_eq!(*r1.unwrap_int32(), 1);
+    assert_eq!(*v1.unwrap_ref_int32(), 1);
+
+    let MyEnumMut::Int32(i) = v1.as_mut() else {
+        panic!()
+    };
+    *i = 5;
+    assert_eq!(v1.unwrap_int32(), 5);
+}
@@ -0,0 +1,84 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(all, !unwrap, !mutable, unwrap_ref(track_caller))]
+enum MyEnum {
+    Int32(i32),
+    Float(f32),
+}
+
+#[generate_enum_helper(owned, views)]
+enum OtherEnum {
+    Int32(i32),
+    Float(f32),
+}
+
+// Without any fields there is nothing to borrow, the RefEnum and the MutEnum are left out.
+#[generate_enum_helper(all)]
+enum Color {
+    Red,
+    Green,
+}
+
+#[generate_enum_helper(all)]
+enum Bounded<T: Clone> {
+    Value(T),
+    Empty,
+}
+
+trait Fallback {
+    fn unwrap_int32(self) -> i32;
+    fn get_mut_int32(&mut self) -> Option<&mut i32>;
+}
+
+// Only used if the presets do not generate these functions.
+impl Fallback for MyEnum {
+    fn unwrap_int32(self) -> i32 {
+        -1
+    }
+
+    fn get_mut_int32(&mut self) -> Option<&mut i32> {
+        None
+    }
+}
+
+#[test]
+fn presets_test() {
+    let mut v1 = MyEnum::Int32(1);
+    assert!(v1.is_int32());
+    assert_eq!(v1.get_ref_int32(), Some(&1));
+    assert_eq!(*v1.unwrap_ref_int32(), 1);
+    assert_eq!(v1.as_ref().to_tag(), MyEnumTag::Int32);
+    assert_eq!(v1.get_mut_int32(), None);
+    assert_eq!(v1.unwrap_int32(), -1);
+
+    let mut v2 = OtherEnum::Float(2.0);
+    assert!(v2.is_float());
+    assert_eq!(v2.to_tag(), OtherEnumTag::Float);
+    let OtherEnumMut::Float(f) = v2.as_mut() else {
+        panic!()
+    };
+    *f = 3.0;
+    assert!(v2.as_ref().is_float());
+    assert_eq!(v2.get_float(), Some(3.0));
+}
+
+#[test]
+fn fieldless_presets_test() {
+    let color = Color::Green;
+    assert!(color.is_green());
+    assert_eq!(color.to_tag(), ColorTag::Green);
+}
+
+#[test]
+fn bounded_presets_test() {
+    let mut v1 = Bounded::Value(1);
+    assert_eq!(v1.as_ref().unwrap_value(), &1);
+    let BoundedMut::Value(value) = v1.as_mut() else {
+        panic!()
+    };
+    *value = 2;
+    assert_eq!(v1.get_value(), Some(2));
+    assert!(Bounded::<i32>::Empty.is_empty());
+}
@@ -0,0 +1,50 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(shared_fields, shared_fields_mut)]
+enum Event {
+    Created { id: u32, name: String },
+    Renamed { id: u32, name: String, old: String },
+    Deleted { id: u32, old: u8 },
+}
+
+#[generate_enum_helper(shared_fields)]
+enum Partial {
+    First { id: u32 },
+    Second { id: u32, parent: u32 },
+    Third(u32),
+}
+
+#[test]
+fn shared_fields_test() {
+    let mut v1 = Event::Renamed {
+        id: 1,
+        name: "b".to_string(),
+        old: "a".to_string(),
+    };
+    assert_eq!(*v1.id(), 1);
+    *v1.id_mut() = 2;
+    assert_eq!(*v1.id(), 2);
+    assert_eq!(v1.name().map(String::as_str), Some("b"));
+    v1.name_mut().unwrap().push('c');
+    assert_eq!(v1.name().map(String::as_str), Some("bc"));
+    assert_eq!(Event::Deleted { id: 3, old: 0 }.name(), None);
+
+    assert_eq!(Partial::Second { id: 1, parent: 2 }.id(), Some(&1));
+    assert_eq!(Partial::Third(3).id(), None);
+}
+
+// `old` has different types, so no accessor is generated for it.
+trait Old {
+    fn old(&self) -> u8 {
+        0
+    }
+}
+
+impl Old for Event {}
+
+#[test]
+fn conflicting_types_test() {
+    assert_eq!(Event::Deleted { id: 3, old: 1 }.old(), 0);
+}
@@ -0,0 +1,82 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(TagEnum(strings))]
+enum Plain {
+    Int32(i32),
+    HTTPServer,
+}
+
+#[generate_enum_helper(TagEnum(strings(rename_all = "kebab-case", case_insensitive)))]
+enum Kebab {
+    Int32(i32),
+    HTTPServer,
+    r#Type,
+}
+
+#[generate_enum_helper(
+    TagEnum(name = Format, strings(rename_all = "SCREAMING_SNAKE_CASE")),
+    rename_all = "camelCase"
+)]
+enum Screaming {
+    JsonLines,
+    Csv,
+}
+
+#[generate_enum_helper(TagEnum(strings(rename_all = "snake_case")))]
+enum Snake {
+    JsonLines,
+}
+
+// `Self::Err` would be ambiguous with the variant `OutcomeTag::Err`.
+#[generate_enum_helper(TagEnum(strings))]
+enum Outcome {
+    Ok(u8),
+    Err(String),
+}
+
+#[test]
+fn as_str_test() {
+    assert_eq!(PlainTag::Int32.as_str(), "Int32");
+    assert_eq!(PlainTag::HTTPServer.as_str(), "HTTPServer");
+    assert_eq!(KebabTag::HTTPServer.as_str(), "http-server");
+    assert_eq!(KebabTag::Type.as_str(), "type");
+    assert_eq!(Format::JsonLines.asStr(), "JSON_LINES");
+    assert_eq!(SnakeTag::JsonLines.as_str(), "json_lines");
+}
+
+#[test]
+fn display_test() {
+    assert_eq!(KebabTag::Int32.to_string(), "int32");
+    assert_eq!(format!("{:>6}|", Format::Csv), "   CSV|");
+}
+
+#[test]
+fn from_str_test() {
+    assert_eq!("Int32".parse(), Ok(PlainTag::Int32));
+    assert!("int32".parse::<PlainTag>().is_err());
+
+    assert_eq!("HTTP-Server".parse(), Ok(KebabTag::HTTPServer));
+    assert_eq!("TYPE".parse(), Ok(KebabTag::Type));
+    assert_eq!("JSON_LINES".parse(), Ok(Format::JsonLines));
+
+    let error = "xml".parse::<Format>().unwrap_err();
+    assert_eq!(error.value, "xml");
+    assert_eq!(
+        error.to_string(),
+        "unknown Format `xml`, expected one of: JSON_LINES, CSV"
+    );
+    let _: &dyn std::error::Error = &error;
+    let _: ParsePlainTagError = "".parse::<PlainTag>().unwrap_err();
+}
+
+#[test]
+fn ok_err_variants_test() {
+    assert_eq!(OutcomeTag::Err.as_str(), "Err");
+    assert_eq!("Ok".parse(), Ok(OutcomeTag::Ok));
+    assert_eq!(
+        "ok".parse::<OutcomeTag>().unwrap_err().to_string(),
+        "unknown OutcomeTag `ok`, expected one of: Ok, Err"
+    );
+}
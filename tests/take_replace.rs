@@ -0,0 +1,27 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[derive(Debug, PartialEq)]
+#[generate_enum_helper(take, replace)]
+enum MyEnum {
+    Text(String),
+    Point { x: i32, y: i32 },
+    Empty,
+}
+
+#[test]
+fn take_replace_test() {
+    let mut v1 = MyEnum::Text("a".to_string());
+    assert_eq!(v1.take_point(MyEnum::Empty), None);
+    assert_eq!(v1.replace_text("b".to_string()), Some("a".to_string()));
+    assert_eq!(v1, MyEnum::Text("b".to_string()));
+    assert_eq!(v1.take_text(MyEnum::Empty), Some("b".to_string()));
+    assert_eq!(v1, MyEnum::Empty);
+    assert_eq!(v1.take_empty(MyEnum::Point { x: 1, y: 2 }), Some(()));
+
+    let slot: &mut MyEnum = &mut v1;
+    assert_eq!(slot.replace_point((3, 4)), Some((1, 2)));
+    assert_eq!(slot.replace_text(String::new()), None);
+    assert_eq!(*slot, MyEnum::Point { x: 3, y: 4 });
+}
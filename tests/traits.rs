@@ -0,0 +1,100 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use enum_utility_macros::generate_enum_helper;
+use enum_utility_traits::{HasMutView, HasRefView, Tagged};
+
+#[generate_enum_helper(TagEnum, RefEnum, MutEnum, traits)]
+enum MyEnum<T> {
+    Value(T),
+    Pair(i32, i32),
+    Point { x: i32, y: i32 },
+    Empty,
+}
+
+#[generate_enum_helper(TagEnum, traits(crate = enum_utility_traits))]
+enum Other {
+    A,
+    B(u8),
+}
+
+fn count_tags<E: Tagged>(values: &[E]) -> HashMap<E::Tag, usize> {
+    let mut counts = HashMap::new();
+    for value in values {
+        *counts.entry(value.tag()).or_default() += 1;
+    }
+    counts
+}
+
+fn first_value<E>(e: &E) -> Option<u8>
+where
+    E: for<'a> HasRefView<Ref<'a> = MyEnumRef<'a, u8>>,
+{
+    match e.view() {
+        MyEnumRef::Value(v) => Some(*v),
+        _ => None,
+    }
+}
+
+fn increment<E>(e: &mut E)
+where
+    E: for<'a> HasMutView<Mut<'a> = MyEnumMut<'a, u8>>,
+{
+    if let MyEnumMut::Value(v) = e.view_mut() {
+        *v += 1;
+    }
+}
+
+#[test]
+fn tagged_test() {
+    let values = [MyEnum::Value(1u8), MyEnum::Empty, MyEnum::Value(2)];
+    let counts = count_tags(&values);
+    assert_eq!(counts[&MyEnumTag::Value], 2);
+    assert_eq!(counts[&MyEnumTag::Empty], 1);
+
+    let mut e = MyEnum::<u8>::Point { x: 1, y: 2 };
+    assert_eq!(e.view().tag(), MyEnumTag::Point);
+    assert_eq!(e.view_mut().tag(), MyEnumTag::Point);
+    assert_eq!(count_tags(&[Other::A, Other::B(1)]).len(), 2);
+}
+
+#[test]
+fn view_test() {
+    let mut e = MyEnum::Value(1u8);
+    increment(&mut e);
+    assert_eq!(first_value(&e), Some(2));
+
+    let mut view = e.view_mut();
+    if let MyEnumMut::Value(v) = view.view_mut() {
+        *v += 1;
+    }
+    assert!(matches!(view.view(), MyEnumRef::Value(3)));
+    assert_eq!(first_value(&MyEnum::<u8>::Pair(1, 2)), None);
+
+    let mut point = MyEnum::<u8>::Point { x: 1, y: 2 };
+    if let MyEnumMut::Point { x, .. } = point.view_mut().view_mut() {
+        *x = 5;
+    }
+    assert!(matches!(point.view(), MyEnumRef::Point { x: 5, y: 2 }));
+}
+
+// The associated types must not be confused with variants of the same name.
+#[generate_enum_helper(TagEnum, RefEnum, MutEnum, traits)]
+enum Ambiguous {
+    One(u8),
+    Ref(u8),
+    Mut(u8),
+    Tag,
+}
+
+#[test]
+fn variant_names_test() {
+    let mut e = Ambiguous::Ref(1);
+    assert!(matches!(e.view(), AmbiguousRef::Ref(1)));
+    if let AmbiguousMut::Ref(v) = e.view_mut().view_mut() {
+        *v = 2;
+    }
+    assert!(matches!(e.view_mut().view(), AmbiguousRef::Ref(2)));
+    assert_eq!(e.tag(), AmbiguousTag::Ref);
+}
@@ -0,0 +1,64 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+// The enum does not implement `Debug`, the error does anyway.
+#[generate_enum_helper(TagEnum, try_unwrap)]
+enum MyEnum<T> {
+    Int32(i32),
+    Value(T),
+    Point { x: i32, y: i32 },
+}
+
+fn sum(v: MyEnum<String>) -> Result<i32, MyEnumWrongVariant<String>> {
+    let (x, y) = v.try_unwrap_point()?;
+    Ok(x + y)
+}
+
+fn boxed(v: MyEnum<String>) -> Result<i32, Box<dyn std::error::Error>> {
+    Ok(v.try_unwrap_int32()?)
+}
+
+#[test]
+fn try_unwrap_test() {
+    assert_eq!(sum(MyEnum::Point { x: 1, y: 2 }).unwrap(), 3);
+
+    let error = sum(MyEnum::Value("a".to_string())).unwrap_err();
+    assert_eq!(error.expected, MyEnumTag::Point);
+    assert_eq!(error.actual, MyEnumTag::Value);
+    assert_eq!(error.value.try_unwrap_value().ok(), Some("a".to_string()));
+
+    let error = boxed(MyEnum::Point { x: 1, y: 2 }).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected MyEnum::Int32, found MyEnum::Point"
+    );
+    assert_eq!(
+        format!("{error:?}"),
+        "MyEnumWrongVariant { expected: Int32, actual: Point, .. }"
+    );
+}
+
+// Without a TagEnum the error names the variants with strings.
+#[generate_enum_helper(try_unwrap)]
+enum Untagged {
+    Int32(i32),
+    r#Float(f32),
+}
+
+#[test]
+fn untagged_test() {
+    assert_eq!(Untagged::Int32(1).try_unwrap_int32().ok(), Some(1));
+
+    let error = Untagged::Float(1.0).try_unwrap_int32().unwrap_err();
+    assert_eq!(error.expected, "Int32");
+    assert_eq!(error.actual, "Float");
+    assert_eq!(
+        error.to_string(),
+        "expected Untagged::Int32, found Untagged::Float"
+    );
+    assert_eq!(
+        format!("{error:?}"),
+        "UntaggedWrongVariant { expected: \"Int32\", actual: \"Float\", .. }"
+    );
+}
@@ -0,0 +1,10 @@
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(from)]
+enum MyEnum<T> {
+    First(u8),
+    Second(u8),
+    Generic(T),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(is)]
+enum MyEnum {
+    #[enum_helper(skip(unknown))]
+    Int32(i32),
+    #[enum_helper(rename = "not a name")]
+    Float(f32),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(TagEnum(strings(rename_all = "lowercase", case_insensitive)))]
+enum MyEnum {
+    Ab(i32),
+    AB(f32),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(is, RefEnum)]
+enum Color {
+    Red,
+    Green,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(is)]
+enum MyEnum {
+    AB(i32),
+    Ab(f32),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(fields)]
+enum MyEnum {
+    #[enum_helper(rename = "2fast")]
+    Fast { x: i32 },
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(is, unknown, TagEnum(color = red), unwrap(vis = pub, fast))]
+enum MyEnum {
+    #[enum_helper(hide)]
+    Int32(i32),
+}
+
+fn main() {}
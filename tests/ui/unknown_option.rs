@@ -0,0 +1,8 @@
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(is, unwrapp)]
+enum MyEnum {
+    Int32(i32),
+}
+
+fn main() {}
@@ -19,7 +19,7 @@ enum MyEnum {
 fn unnamed_enum_attributes_test() {
     let mut m1 = MyEnum::Int32(1);
     let r1 = m1.as_ref();
-    format!("{m1:?} {r1:?}");
+    let _ = format!("{m1:?} {r1:?}");
 
     #[allow(unused_assignments)]
     let mut m2 = m1.clone();
@@ -28,7 +28,7 @@ fn unnamed_enum_attributes_test() {
     assert_ne!(m1, m2);
 
     let g1 = m1.as_mut();
-    format!("{g1:?}");
+    let _ = format!("{g1:?}");
     let MyEnumMut::Int32(i) = g1 else {
         panic!()
     };
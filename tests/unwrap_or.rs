@@ -0,0 +1,52 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+struct NoDefault;
+
+#[generate_enum_helper(
+    RefEnum,
+    MutEnum,
+    as_ref,
+    as_mut,
+    unwrap_or,
+    unwrap_or_default,
+    unwrap_ref_or,
+    unwrap_mut_or
+)]
+enum MyEnum {
+    Int32(i32),
+    Point {
+        x: i32,
+        y: i32,
+    },
+    #[enum_helper(skip(unwrap_or_default))]
+    Other(NoDefault),
+}
+
+#[test]
+fn unwrap_or_test() {
+    let mut v1 = MyEnum::Int32(1);
+    assert_eq!(v1.unwrap_ref_int32_or(&2), &1);
+    assert_eq!(v1.unwrap_ref_point_or((&3, &4)), (&3, &4));
+    assert_eq!(v1.as_ref().unwrap_point_or_else(|| (&5, &6)), (&5, &6));
+
+    let (mut fallback, mut y) = (7, 0);
+    *v1.unwrap_mut_int32_or(&mut fallback) += 1;
+    *v1.unwrap_mut_point_or_else(|| (&mut fallback, &mut y)).0 += 1;
+    assert_eq!(fallback, 8);
+    let mut other = 9;
+    *v1.as_mut().unwrap_int32_or(&mut other) += 1;
+
+    assert_eq!(MyEnum::Int32(1).unwrap_point_or_default(), (0, 0));
+    assert_eq!(MyEnum::Other(NoDefault).unwrap_int32_or(3), 3);
+    assert_eq!(MyEnum::Point { x: 1, y: 2 }.unwrap_int32_or_else(|| 4), 4);
+    assert_eq!(v1.unwrap_int32_or_default(), 3);
+}
+
+// Presets do not enable `unwrap_or_default`, so they work with payloads without `Default`.
+#[generate_enum_helper(all)]
+enum OtherEnum {
+    Int32(i32),
+    Other(NoDefault),
+}
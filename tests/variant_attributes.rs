@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+
+use enum_utility_macros::generate_enum_helper;
+
+#[generate_enum_helper(TagEnum, RefEnum, is, unwrap, get, to_tag, as_ref)]
+#[derive(Debug, PartialEq)]
+enum MyEnum {
+    #[enum_helper(rename = "int")]
+    Int32(i32),
+    #[enum_helper(skip(unwrap, get))]
+    Float(f32),
+    #[enum_helper(skip)]
+    Internal(u8),
+    #[enum_helper(rename = type)]
+    Type(char),
+}
+
+impl MyEnum {
+    // Would collide with the generated function if `Internal` was not skipped.
+    fn is_internal(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn variant_attributes_test() {
+    let v1 = MyEnum::Int32(1);
+    let v2 = MyEnum::Float(2.0);
+    let v3 = MyEnum::Internal(3);
+    let v4 = MyEnum::Type('a');
+
+    assert!(v1.is_int());
+    assert!(v2.is_float());
+    assert!(v3.is_internal());
+    assert!(v4.is_type());
+
+    assert_eq!(v3.to_tag(), MyEnumTag::Internal);
+    assert!(MyEnumTag::Int32.is_int());
+    assert!(v2.as_ref().is_float());
+    assert_eq!(*v1.as_ref().unwrap_int(), 1);
+
+    assert_eq!(v4.get_type(), Some('a'));
+    assert_eq!(v1.unwrap_int(), 1);
+}
@@ -0,0 +1,40 @@
+#![allow(dead_code)]
+
+mod inner {
+    use enum_utility_macros::generate_enum_helper;
+
+    #[generate_enum_helper(
+        helpers_vis = pub(crate),
+        TagEnum(vis = pub(crate)),
+        RefEnum,
+        is,
+        unwrap(vis = pub(super)),
+        get(vis = pub(self)),
+        to_tag,
+        as_ref
+    )]
+    pub enum MyEnum {
+        Int32(i32),
+        Unit,
+    }
+
+    pub fn get_int32(e: MyEnum) -> Option<i32> {
+        e.get_int32()
+    }
+}
+
+use inner::{MyEnum, MyEnumRef, MyEnumTag};
+
+#[test]
+fn visibility_test() {
+    let v1 = MyEnum::Int32(1);
+    let v2 = MyEnum::Unit;
+
+    assert!(v1.is_int32());
+    assert!(v2.is_unit());
+    assert_eq!(v1.to_tag(), MyEnumTag::Int32);
+    assert!(matches!(v1.as_ref(), MyEnumRef::Int32(&1)));
+    assert!(v1.as_ref().is_int32());
+    assert_eq!(inner::get_int32(v2), None);
+    assert_eq!(v1.unwrap_int32(), 1);
+}